@@ -1,70 +1,178 @@
+use core::ops::Range;
+
 use auto_delegate::{delegate, Delegate};
 
-use crate::bpb::fat32::{Fat32BootSector, Fat32BootSectorReadable};
-use crate::bpb::general::buffer::GeneralBootSector;
-use crate::bpb::general::GeneralBootSectorReadable;
+use crate::bpb::params::BiosParameterBlock;
 use crate::dir::data::DataEntries;
 use crate::dir::data::dir::DirEntries;
-use crate::error::FatResult;
+use crate::error::{FatError, FatResult};
 use crate::FatDeviceAccessible;
+use crate::options::MountOptions;
 
 mod general;
 mod fat32;
+pub mod fs_info;
+pub mod params;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FatType {
+    Fat12,
+    Fat16,
+    Fat32,
+}
+
+
+impl FatType {
+    pub const fn from_cluster_count(cluster_count: usize) -> FatType {
+        if cluster_count < 4085 {
+            Self::Fat12
+        } else if cluster_count < 65525 {
+            Self::Fat16
+        } else {
+            Self::Fat32
+        }
+    }
+}
+
 
 #[delegate]
 pub trait BpbReadable {
+    fn fat_type(&self) -> FatResult<FatType>;
+
+
     fn data_cluster_offset_at(&self, cluster_no: usize) -> FatResult<usize>;
+
+
+    fn fat_entry_offset_at(&self, cluster_no: usize) -> FatResult<usize>;
+
+
+    fn bytes_per_cluster(&self) -> FatResult<usize>;
+
+
+    fn root_dir_cluster_no(&self) -> FatResult<usize>;
+
+
+    fn fat_count(&self) -> FatResult<usize>;
+
+
+    fn fat_bytes(&self) -> FatResult<usize>;
+
+
+    fn cluster_count(&self) -> FatResult<usize>;
+
+
+    fn fs_info_offset(&self) -> FatResult<Option<usize>>;
+
+
+    fn root_dir_region(&self) -> FatResult<Option<Range<usize>>>;
 }
 
 
 #[derive(Clone, Delegate)]
-pub struct BpbFat32<D>
+pub struct Bpb<D>
     where D: FatDeviceAccessible
 {
-    general: GeneralBootSector<D>,
-    fat32: Fat32BootSector<D>,
+    params: BiosParameterBlock,
 
     #[to(FatDeviceAccessible)]
     pub(crate) device: D,
 }
 
 
-impl<D> BpbFat32<D>
+impl<D> Bpb<D>
     where D: FatDeviceAccessible + Clone
 {
     #[inline]
-    pub fn new(device: D) -> BpbFat32<D> {
-        Self {
-            general: GeneralBootSector::new(device.clone()),
-            fat32: Fat32BootSector::new(device.clone()),
+    pub fn new(device: D) -> FatResult<Bpb<D>> {
+        Ok(Self {
+            params: BiosParameterBlock::new(&device)?,
             device,
-        }
+        })
     }
 
 
     #[inline]
-    pub fn root_dir(&self) -> FatResult<DataEntries<BpbFat32<D>>> {
-        Ok(DataEntries::new(DirEntries::root(self.clone(), self.data_region_offset_fat32()?)))
+    pub const fn params(&self) -> &BiosParameterBlock {
+        &self.params
     }
 
 
-    pub(crate) fn data_region_offset_fat32(&self) -> FatResult<usize> {
-        let bytes_per_sector = self.general.bytes_per_sector()? as usize;
-        let reserve_bytes = self.general.reserved_sectors()? as usize * bytes_per_sector;
-        let fat_bytes = self.general.num_fats()? as usize * self.fat32.sectors_per_fat()? as usize * bytes_per_sector;
-
-        Ok(reserve_bytes + fat_bytes)
+    #[inline]
+    pub fn root_dir(&self) -> FatResult<DataEntries<Bpb<D>>> {
+        Ok(DataEntries::new(DirEntries::root(self.clone(), MountOptions::new())?))
     }
 }
 
 
-impl<D> BpbReadable for BpbFat32<D>
+impl<D> BpbReadable for Bpb<D>
     where D: FatDeviceAccessible + Clone
 {
     #[inline]
+    fn fat_type(&self) -> FatResult<FatType> {
+        Ok(self.params.fat_type())
+    }
+
+
     fn data_cluster_offset_at(&self, cluster_no: usize) -> FatResult<usize> {
-        let offset = (cluster_no - 2) * self.general.bytes_per_sector()? as usize * self.general.sectors_per_cluster()? as usize;
-        Ok(offset + self.data_region_offset_fat32()?)
+        let index = cluster_no
+            .checked_sub(2)
+            .filter(|index| *index < self.params.cluster_count())
+            .ok_or(FatError::InvalidClusterNo(cluster_no))?;
+
+        Ok(self.params.data_offset() + index * self.params.bytes_per_cluster())
+    }
+
+
+    fn fat_entry_offset_at(&self, cluster_no: usize) -> FatResult<usize> {
+        let offset = match self.params.fat_type() {
+            FatType::Fat12 => cluster_no + cluster_no / 2,
+            FatType::Fat16 => cluster_no * 2,
+            FatType::Fat32 => cluster_no * 4,
+        };
+
+        Ok(self.params.fat_offset() + offset)
+    }
+
+
+    #[inline]
+    fn bytes_per_cluster(&self) -> FatResult<usize> {
+        Ok(self.params.bytes_per_cluster())
+    }
+
+
+    #[inline]
+    fn root_dir_cluster_no(&self) -> FatResult<usize> {
+        Ok(self.params.root_cluster_no())
+    }
+
+
+    #[inline]
+    fn fat_count(&self) -> FatResult<usize> {
+        Ok(self.params.fat_count())
+    }
+
+
+    #[inline]
+    fn fat_bytes(&self) -> FatResult<usize> {
+        Ok(self.params.fat_bytes())
+    }
+
+
+    #[inline]
+    fn cluster_count(&self) -> FatResult<usize> {
+        Ok(self.params.cluster_count())
+    }
+
+
+    #[inline]
+    fn fs_info_offset(&self) -> FatResult<Option<usize>> {
+        Ok(self.params.fs_info_offset())
+    }
+
+
+    #[inline]
+    fn root_dir_region(&self) -> FatResult<Option<Range<usize>>> {
+        Ok(self.params.root_dir_region())
     }
 }
 
@@ -84,12 +192,63 @@ pub(crate) fn buff_read_u32(buff: &[u8], index: usize) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    use crate::bpb::BpbFat32;
-    use crate::test::file_device;
+    use crate::bpb::{Bpb, BpbReadable, FatType};
+    use crate::error::FatError;
+    use crate::test::{fat12_memory_device, fat16_memory_device, file_device};
+
+    #[test]
+    fn it_fat_entry_offset_at() {
+        let bpb = Bpb::new(file_device()).unwrap();
+        assert_eq!(bpb.fat_entry_offset_at(3).unwrap(), 0x400C);
+    }
+
+
+    #[test]
+    fn it_data_cluster_offset_at() {
+        let bpb = Bpb::new(file_device()).unwrap();
+        assert_eq!(bpb.data_cluster_offset_at(3).unwrap(), 0x102400);
+    }
+
+
+    #[test]
+    fn it_failed_data_cluster_offset_out_of_range() {
+        let bpb = Bpb::new(file_device()).unwrap();
+        let cluster_count = bpb.cluster_count().unwrap();
+
+        assert_eq!(bpb.data_cluster_offset_at(0), Err(FatError::InvalidClusterNo(0)));
+        assert_eq!(bpb.data_cluster_offset_at(1), Err(FatError::InvalidClusterNo(1)));
+        assert_eq!(bpb.data_cluster_offset_at(cluster_count + 2), Err(FatError::InvalidClusterNo(cluster_count + 2)));
+        assert!(bpb.data_cluster_offset_at(cluster_count + 1).is_ok());
+    }
+
+
+    #[test]
+    fn it_fat_type_from_cluster_count() {
+        assert_eq!(FatType::from_cluster_count(4084), FatType::Fat12);
+        assert_eq!(FatType::from_cluster_count(4085), FatType::Fat16);
+        assert_eq!(FatType::from_cluster_count(65524), FatType::Fat16);
+        assert_eq!(FatType::from_cluster_count(65525), FatType::Fat32);
+    }
+
+
+    #[test]
+    fn it_fat12_geometry() {
+        let bpb = Bpb::new(fat12_memory_device()).unwrap();
+        assert_eq!(bpb.fat_type(), Ok(FatType::Fat12));
+        assert_eq!(bpb.root_dir_cluster_no(), Ok(0));
+        assert_eq!(bpb.root_dir_region(), Ok(Some(0x2600..0x4200)));
+        assert_eq!(bpb.data_cluster_offset_at(2), Ok(0x4200));
+        assert_eq!(bpb.fat_entry_offset_at(3), Ok(0x204));
+        assert_eq!(bpb.fs_info_offset(), Ok(None));
+    }
+
 
     #[test]
-    fn it_data_region_offset_fat32() {
-        let bpb = BpbFat32::new(file_device());
-        assert_eq!(bpb.data_region_offset_fat32().unwrap(), 0x102000);
+    fn it_fat16_geometry() {
+        let bpb = Bpb::new(fat16_memory_device()).unwrap();
+        assert_eq!(bpb.fat_type(), Ok(FatType::Fat16));
+        assert_eq!(bpb.root_dir_region(), Ok(Some(0x8800..0xC800)));
+        assert_eq!(bpb.data_cluster_offset_at(2), Ok(0xC800));
+        assert_eq!(bpb.fat_entry_offset_at(3), Ok(0x806));
     }
 }
@@ -6,6 +6,9 @@ pub trait Fat32BootSectorReadable {
 
 
     fn root_cluster_no(&self) -> FatResult<u32>;
+
+
+    fn fs_info_sector(&self) -> FatResult<u16>;
 }
 
 
@@ -38,6 +41,12 @@ impl<D> Fat32BootSectorReadable for Fat32BootSector<D>
     fn root_cluster_no(&self) -> FatResult<u32> {
         self.device.read_u32(44)
     }
+
+
+    #[inline]
+    fn fs_info_sector(&self) -> FatResult<u16> {
+        self.device.read_u16(48)
+    }
 }
 
 
@@ -51,4 +60,11 @@ mod tests {
         let fat32 = Fat32BootSector::new(file_device());
         assert_eq!(fat32.root_cluster_no().unwrap(), 2);
     }
-}
\ No newline at end of file
+
+
+    #[test]
+    fn it_fs_info_sector() {
+        let fat32 = Fat32BootSector::new(file_device());
+        assert_eq!(fat32.fs_info_sector().unwrap(), 1);
+    }
+}
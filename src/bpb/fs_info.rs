@@ -0,0 +1,133 @@
+use crate::error::FatResult;
+use crate::FatDeviceAccessible;
+
+pub const LEAD_SIGNATURE: u32 = 0x4161_5252;
+
+pub const STRUCT_SIGNATURE: u32 = 0x6141_7272;
+
+pub const TRAIL_SIGNATURE: u32 = 0xAA55_0000;
+
+pub const UNKNOWN_COUNT: u32 = 0xFFFF_FFFF;
+
+pub const UNKNOWN_NEXT_FREE: u32 = 0xFFFF_FFFF;
+
+
+#[derive(Clone)]
+pub struct FsInfo<D> {
+    device: D,
+
+    offset: usize,
+}
+
+
+impl<D> FsInfo<D> where D: FatDeviceAccessible {
+    #[inline]
+    pub const fn new(device: D, offset: usize) -> FsInfo<D> {
+        Self {
+            device,
+            offset,
+        }
+    }
+
+
+    pub fn is_valid(&self) -> FatResult<bool> {
+        Ok(self.device.read_u32(self.offset)? == LEAD_SIGNATURE
+            && self.device.read_u32(self.offset + 484)? == STRUCT_SIGNATURE
+            && self.device.read_u32(self.offset + 508)? == TRAIL_SIGNATURE)
+    }
+
+
+    #[inline]
+    pub fn free_count(&self) -> FatResult<u32> {
+        self.device.read_u32(self.offset + 488)
+    }
+
+
+    pub fn known_free_count(&self) -> FatResult<Option<usize>> {
+        match self.free_count()? {
+            UNKNOWN_COUNT => Ok(None),
+            free_count => Ok(Some(free_count as usize))
+        }
+    }
+
+
+    #[inline]
+    pub fn write_free_count(&mut self, free_count: u32) -> FatResult {
+        self.device.write_u32(self.offset + 488, free_count)
+    }
+
+
+    pub fn adjust_free_count(&mut self, delta: isize) -> FatResult {
+        match self.free_count()? {
+            UNKNOWN_COUNT => Ok(()),
+            free_count => self.write_free_count(free_count.saturating_add_signed(delta as i32))
+        }
+    }
+
+
+    #[inline]
+    pub fn next_free(&self) -> FatResult<u32> {
+        self.device.read_u32(self.offset + 492)
+    }
+
+
+    #[inline]
+    pub fn write_next_free(&mut self, cluster_no: u32) -> FatResult {
+        self.device.write_u32(self.offset + 492, cluster_no)
+    }
+
+
+    pub fn next_free_hint(&self) -> FatResult<Option<usize>> {
+        match self.next_free()? {
+            UNKNOWN_NEXT_FREE => Ok(None),
+            cluster_no => Ok(Some(cluster_no as usize))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bpb::fs_info::{FsInfo, UNKNOWN_COUNT, UNKNOWN_NEXT_FREE};
+    use crate::test::{file_device, memory_device};
+
+    #[test]
+    fn it_valid_signatures() {
+        let fs_info = FsInfo::new(file_device(), 0x200);
+        assert!(fs_info.is_valid().unwrap());
+    }
+
+
+    #[test]
+    fn it_free_count() {
+        let fs_info = FsInfo::new(file_device(), 0x200);
+        assert_eq!(fs_info.free_count().unwrap(), 130037);
+    }
+
+
+    #[test]
+    fn it_adjust_free_count() {
+        let mut fs_info = FsInfo::new(memory_device(), 0x200);
+        fs_info.adjust_free_count(-2).unwrap();
+        assert_eq!(fs_info.free_count().unwrap(), 130035);
+    }
+
+
+    #[test]
+    fn it_next_free_hint() {
+        let mut fs_info = FsInfo::new(memory_device(), 0x200);
+        assert_eq!(fs_info.next_free_hint(), Ok(Some(5)));
+
+        fs_info.write_next_free(UNKNOWN_NEXT_FREE).unwrap();
+        assert_eq!(fs_info.next_free_hint(), Ok(None));
+    }
+
+
+    #[test]
+    fn it_ignore_adjust_if_unknown_free_count() {
+        let mut fs_info = FsInfo::new(memory_device(), 0x200);
+        fs_info.write_free_count(UNKNOWN_COUNT).unwrap();
+        fs_info.adjust_free_count(1).unwrap();
+        assert_eq!(fs_info.free_count().unwrap(), UNKNOWN_COUNT);
+    }
+}
@@ -29,12 +29,29 @@ pub trait GeneralBootSectorReadable {
     fn num_fats(&self) -> FatResult<u8>;
 
 
+    fn root_entry_count(&self) -> FatResult<u16>;
+
+
+    fn sectors_per_fat16(&self) -> FatResult<u16>;
+
+
+    fn boot_signature(&self) -> FatResult<u16>;
+
+
     #[cfg(feature = "alloc")]
     fn oem_name(&self) -> FatResult<CString> {
         Ok(unsafe { CString::from_vec_unchecked(Vec::from(self.oem_name_buff()?)) })
     }
 
 
+    fn checked_bytes_per_sector(&self) -> FatResult<u16> {
+        match self.bytes_per_sector()? {
+            valid @ (512 | 1024 | 2048 | 4096) => Ok(valid),
+            invalid => Err(FatError::InvalidBytesPerSector(invalid))
+        }
+    }
+
+
     fn checked_sectors_per_cluster(&self) -> FatResult<u8> {
         match self.sectors_per_cluster()? {
             valid @ (1 | 2 | 4 | 8 | 16 | 32 | 64 | 128) => Ok(valid),
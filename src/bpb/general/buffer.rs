@@ -64,6 +64,24 @@ impl<D> GeneralBootSectorReadable for GeneralBootSector<D>
     fn num_fats(&self) -> FatResult<u8> {
         self.device.read_u8(16)
     }
+
+
+    #[inline]
+    fn root_entry_count(&self) -> FatResult<u16> {
+        self.device.read_u16(17)
+    }
+
+
+    #[inline]
+    fn sectors_per_fat16(&self) -> FatResult<u16> {
+        self.device.read_u16(22)
+    }
+
+
+    #[inline]
+    fn boot_signature(&self) -> FatResult<u16> {
+        self.device.read_u16(510)
+    }
 }
 
 
@@ -0,0 +1,323 @@
+use core::ops::Range;
+
+use crate::bpb::FatType;
+use crate::bpb::fat32::{Fat32BootSector, Fat32BootSectorReadable};
+use crate::bpb::general::buffer::GeneralBootSector;
+use crate::bpb::general::GeneralBootSectorReadable;
+use crate::error::{FatDeviceError, FatError, FatResult};
+use crate::FatDeviceAccessible;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BiosParameterBlock {
+    fat_type: FatType,
+
+    bytes_per_sector: usize,
+
+    bytes_per_cluster: usize,
+
+    total_sectors: usize,
+
+    fat_count: usize,
+
+    fat_offset: usize,
+
+    fat_bytes: usize,
+
+    root_dir_offset: usize,
+
+    root_entry_count: usize,
+
+    root_cluster_no: usize,
+
+    data_offset: usize,
+
+    cluster_count: usize,
+
+    fs_info_offset: Option<usize>,
+}
+
+
+impl BiosParameterBlock {
+    pub fn new<D>(device: &D) -> FatResult<BiosParameterBlock>
+        where D: FatDeviceAccessible
+    {
+        let mut sector = BootSector([0; 0x200]);
+        device.read(&mut sector.0, 0, 0x200)?;
+
+        let general = GeneralBootSector::new(sector.clone());
+        let fat32 = Fat32BootSector::new(sector);
+
+        let signature = general.boot_signature()?;
+        if signature != 0xAA55 {
+            return Err(FatError::InvalidBootSignature(signature));
+        }
+
+        let bytes_per_sector = general.checked_bytes_per_sector()? as usize;
+        let sectors_per_cluster = general.checked_sectors_per_cluster()? as usize;
+        let fat_count = general.num_fats()? as usize;
+        if fat_count == 0 {
+            return Err(FatError::InvalidNumFats);
+        }
+        let reserved_sectors = general.reserved_sectors()? as usize;
+        if reserved_sectors == 0 {
+            return Err(FatError::InvalidReservedSectors);
+        }
+
+        let sectors_per_fat = match general.sectors_per_fat16()? {
+            0 => fat32.sectors_per_fat()? as usize,
+            sectors => sectors as usize
+        };
+        let total_sectors = match general.total_sector16()? {
+            0 => general.total_sector32()? as usize,
+            sectors => sectors as usize
+        };
+        let root_entry_count = general.root_entry_count()? as usize;
+
+        let fat_offset = reserved_sectors * bytes_per_sector;
+        let fat_bytes = sectors_per_fat * bytes_per_sector;
+        let root_dir_offset = fat_offset + fat_count * fat_bytes;
+        let data_offset = root_dir_offset + (root_entry_count * 0x20).div_ceil(bytes_per_sector) * bytes_per_sector;
+
+        let data_sector = data_offset / bytes_per_sector;
+        if total_sectors <= data_sector {
+            return Err(FatError::InvalidTotalSectors(total_sectors, data_sector));
+        }
+
+        let cluster_count = (total_sectors - data_sector) / sectors_per_cluster;
+        let fat_type = FatType::from_cluster_count(cluster_count);
+        if (fat_type == FatType::Fat32) != (root_entry_count == 0) {
+            return Err(FatError::InvalidRootEntryCount(root_entry_count as u16, fat_type));
+        }
+
+        let entry_bits = match fat_type {
+            FatType::Fat12 => 12,
+            FatType::Fat16 => 16,
+            FatType::Fat32 => 32,
+        };
+        if fat_bytes * 8 / entry_bits < cluster_count + 2 {
+            return Err(FatError::InvalidSectorsPerFat(sectors_per_fat, cluster_count));
+        }
+
+        let (root_cluster_no, fs_info_offset) = if fat_type == FatType::Fat32 {
+            let root_cluster_no = fat32.root_cluster_no()?;
+            if !(2..(cluster_count + 2)).contains(&(root_cluster_no as usize)) {
+                return Err(FatError::InvalidRootCluster(root_cluster_no));
+            }
+
+            let fs_info_offset = match fat32.fs_info_sector()? {
+                0 | 0xFFFF => None,
+                sector => Some(sector as usize * bytes_per_sector)
+            };
+            (root_cluster_no as usize, fs_info_offset)
+        } else {
+            (0, None)
+        };
+
+        Ok(Self {
+            fat_type,
+            bytes_per_sector,
+            bytes_per_cluster: bytes_per_sector * sectors_per_cluster,
+            total_sectors,
+            fat_count,
+            fat_offset,
+            fat_bytes,
+            root_dir_offset,
+            root_entry_count,
+            root_cluster_no,
+            data_offset,
+            cluster_count,
+            fs_info_offset,
+        })
+    }
+
+
+    #[inline]
+    pub const fn fat_type(&self) -> FatType {
+        self.fat_type
+    }
+
+
+    #[inline]
+    pub const fn bytes_per_sector(&self) -> usize {
+        self.bytes_per_sector
+    }
+
+
+    #[inline]
+    pub const fn bytes_per_cluster(&self) -> usize {
+        self.bytes_per_cluster
+    }
+
+
+    #[inline]
+    pub const fn total_sectors(&self) -> usize {
+        self.total_sectors
+    }
+
+
+    #[inline]
+    pub const fn fat_count(&self) -> usize {
+        self.fat_count
+    }
+
+
+    #[inline]
+    pub const fn fat_offset(&self) -> usize {
+        self.fat_offset
+    }
+
+
+    #[inline]
+    pub const fn fat_bytes(&self) -> usize {
+        self.fat_bytes
+    }
+
+
+    #[inline]
+    pub const fn root_cluster_no(&self) -> usize {
+        self.root_cluster_no
+    }
+
+
+    #[inline]
+    pub const fn data_offset(&self) -> usize {
+        self.data_offset
+    }
+
+
+    #[inline]
+    pub const fn cluster_count(&self) -> usize {
+        self.cluster_count
+    }
+
+
+    #[inline]
+    pub const fn fs_info_offset(&self) -> Option<usize> {
+        self.fs_info_offset
+    }
+
+
+    pub fn root_dir_region(&self) -> Option<Range<usize>> {
+        match self.fat_type {
+            FatType::Fat32 => None,
+            _ => Some(self.root_dir_offset..(self.root_dir_offset + self.root_entry_count * 0x20))
+        }
+    }
+}
+
+
+#[derive(Clone)]
+struct BootSector([u8; 0x200]);
+
+
+impl FatDeviceAccessible for BootSector {
+    fn read(&self, buff: &mut [u8], offset: usize, bytes: usize) -> Result<(), FatDeviceError> {
+        buff.copy_from_slice(&self.0[offset..(offset + bytes)]);
+        Ok(())
+    }
+
+
+    fn write(&mut self, buff: &[u8], offset: usize) -> Result<(), FatDeviceError> {
+        self.0[offset..(offset + buff.len())].copy_from_slice(buff);
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bpb::FatType;
+    use crate::bpb::params::BiosParameterBlock;
+    use crate::error::FatError;
+    use crate::FatDeviceAccessible;
+    use crate::test::{fat12_memory_device, fat16_memory_device, file_device, memory_device};
+
+    #[test]
+    fn it_fat32_geometry() {
+        let params = BiosParameterBlock::new(&file_device()).unwrap();
+
+        assert_eq!(params.fat_type(), FatType::Fat32);
+        assert_eq!(params.fat_offset(), 0x4000);
+        assert_eq!(params.fat_bytes(), 1016 * 0x200);
+        assert_eq!(params.data_offset(), 0x102000);
+        assert_eq!(params.bytes_per_cluster(), 0x400);
+        assert_eq!(params.cluster_count(), (0x40000 - 0x102000 / 0x200) / 2);
+        assert_eq!(params.root_cluster_no(), 2);
+        assert_eq!(params.fs_info_offset(), Some(0x200));
+        assert_eq!(params.root_dir_region(), None);
+    }
+
+
+    #[test]
+    fn it_validate_volumes() {
+        assert_eq!(BiosParameterBlock::new(&fat12_memory_device()).map(|params| params.fat_type()), Ok(FatType::Fat12));
+        assert_eq!(BiosParameterBlock::new(&fat16_memory_device()).map(|params| params.fat_type()), Ok(FatType::Fat16));
+    }
+
+
+    #[test]
+    fn it_failed_validate_boot_signature() {
+        let mut device = fat12_memory_device();
+        device.write_u16(510, 0).unwrap();
+        assert_eq!(BiosParameterBlock::new(&device), Err(FatError::InvalidBootSignature(0)));
+    }
+
+
+    #[test]
+    fn it_failed_validate_bytes_per_sector() {
+        let mut device = fat12_memory_device();
+        device.write_u16(11, 768).unwrap();
+        assert_eq!(BiosParameterBlock::new(&device), Err(FatError::InvalidBytesPerSector(768)));
+    }
+
+
+    #[test]
+    fn it_failed_validate_sectors_per_cluster() {
+        let mut device = fat12_memory_device();
+        device.write_u8(13, 3).unwrap();
+        assert_eq!(BiosParameterBlock::new(&device), Err(FatError::InvalidSecPerClus(3)));
+    }
+
+
+    #[test]
+    fn it_failed_validate_num_fats_and_reserved_sectors() {
+        let mut device = fat12_memory_device();
+        device.write_u16(14, 0).unwrap();
+        assert_eq!(BiosParameterBlock::new(&device), Err(FatError::InvalidReservedSectors));
+
+        device.write_u8(16, 0).unwrap();
+        assert_eq!(BiosParameterBlock::new(&device), Err(FatError::InvalidNumFats));
+    }
+
+
+    #[test]
+    fn it_failed_validate_total_sectors() {
+        let mut device = fat12_memory_device();
+        device.write_u16(19, 0x10).unwrap();
+        assert_eq!(BiosParameterBlock::new(&device), Err(FatError::InvalidTotalSectors(0x10, 0x21)));
+    }
+
+
+    #[test]
+    fn it_failed_validate_small_fat() {
+        let mut device = fat12_memory_device();
+        device.write_u16(22, 1).unwrap();
+        assert_eq!(BiosParameterBlock::new(&device), Err(FatError::InvalidSectorsPerFat(1, 2863)));
+    }
+
+
+    #[test]
+    fn it_failed_validate_root_entry_count() {
+        let mut device = fat16_memory_device();
+        device.write_u16(17, 0).unwrap();
+        assert_eq!(BiosParameterBlock::new(&device), Err(FatError::InvalidRootEntryCount(0, FatType::Fat16)));
+    }
+
+
+    #[test]
+    fn it_failed_validate_root_cluster() {
+        let mut device = memory_device();
+        device.write_u32(44, 1).unwrap();
+        assert_eq!(BiosParameterBlock::new(&device), Err(FatError::InvalidRootCluster(1)));
+    }
+}
@@ -3,6 +3,10 @@ use auto_delegate::delegate;
 use crate::bpb::{buff_read_u16, buff_read_u32};
 use crate::error::{FatDeviceError, FatResult};
 
+pub mod block;
+pub mod cache;
+pub mod offset;
+
 #[delegate]
 pub trait FatDeviceAccessible {
     fn read(&self, buff: &mut [u8], offset: usize, bytes: usize) -> Result<(), FatDeviceError>;
@@ -11,6 +15,11 @@ pub trait FatDeviceAccessible {
     fn write(&mut self, buff: &[u8], offset: usize) -> Result<(), FatDeviceError>;
 
 
+    fn flush(&mut self) -> Result<(), FatDeviceError> {
+        Ok(())
+    }
+
+
     fn read_u8(&self, offset: usize) -> FatResult<u8> {
         let mut buff = [0; 1];
         self.read(&mut buff, offset, 1)?;
@@ -33,4 +42,22 @@ pub trait FatDeviceAccessible {
 
         Ok(buff_read_u32(&buff, 0))
     }
+
+
+    fn write_u8(&mut self, offset: usize, value: u8) -> FatResult {
+        self.write(&[value], offset)?;
+        Ok(())
+    }
+
+
+    fn write_u16(&mut self, offset: usize, value: u16) -> FatResult {
+        self.write(&value.to_le_bytes(), offset)?;
+        Ok(())
+    }
+
+
+    fn write_u32(&mut self, offset: usize, value: u32) -> FatResult {
+        self.write(&value.to_le_bytes(), offset)?;
+        Ok(())
+    }
 }
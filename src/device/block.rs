@@ -0,0 +1,215 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::device::FatDeviceAccessible;
+use crate::error::{FatDeviceError, FatError, FatResult};
+
+/// Buffers passed to `read_blocks` and `write_blocks` are always a multiple of `block_size()` long.
+pub trait BlockDevice {
+    fn block_size(&self) -> usize;
+
+
+    fn read_blocks(&self, lba: usize, buff: &mut [u8]) -> Result<(), FatDeviceError>;
+
+
+    fn write_blocks(&mut self, lba: usize, buff: &[u8]) -> Result<(), FatDeviceError>;
+}
+
+
+/// Each clone owns its scratch block used for unaligned heads and tails.
+#[derive(Clone, Debug)]
+pub struct BlockDeviceAdapter<B> {
+    device: B,
+
+    sector: RefCell<Vec<u8>>,
+}
+
+
+impl<B> BlockDeviceAdapter<B>
+    where B: BlockDevice
+{
+    pub fn new(device: B) -> FatResult<BlockDeviceAdapter<B>> {
+        let block_size = device.block_size();
+        if block_size == 0 {
+            return Err(FatError::InvalidBlockSize);
+        }
+
+        Ok(Self {
+            device,
+            sector: RefCell::new(vec![0; block_size]),
+        })
+    }
+
+
+    #[inline]
+    pub fn into_inner(self) -> B {
+        self.device
+    }
+}
+
+
+impl<B> FatDeviceAccessible for BlockDeviceAdapter<B>
+    where B: BlockDevice
+{
+    fn read(&self, buff: &mut [u8], offset: usize, bytes: usize) -> Result<(), FatDeviceError> {
+        let block_size = self.device.block_size();
+        let mut sector = self.sector.borrow_mut();
+        let mut pos = 0;
+
+        while pos < bytes {
+            let lba = (offset + pos) / block_size;
+            let head = (offset + pos) % block_size;
+            let len = (block_size - head).min(bytes - pos);
+
+            if head == 0 && len == block_size {
+                let blocks = (bytes - pos) / block_size;
+                self.device.read_blocks(lba, &mut buff[pos..(pos + blocks * block_size)])?;
+                pos += blocks * block_size;
+            } else {
+                self.device.read_blocks(lba, &mut sector)?;
+                buff[pos..(pos + len)].copy_from_slice(&sector[head..(head + len)]);
+                pos += len;
+            }
+        }
+
+        Ok(())
+    }
+
+
+    fn write(&mut self, buff: &[u8], offset: usize) -> Result<(), FatDeviceError> {
+        let block_size = self.device.block_size();
+        let mut sector = self.sector.borrow_mut();
+        let mut pos = 0;
+
+        while pos < buff.len() {
+            let lba = (offset + pos) / block_size;
+            let head = (offset + pos) % block_size;
+            let len = (block_size - head).min(buff.len() - pos);
+
+            if head == 0 && len == block_size {
+                let blocks = (buff.len() - pos) / block_size;
+                self.device.write_blocks(lba, &buff[pos..(pos + blocks * block_size)])?;
+                pos += blocks * block_size;
+            } else {
+                self.device.read_blocks(lba, &mut sector)?;
+                sector[head..(head + len)].copy_from_slice(&buff[pos..(pos + len)]);
+                self.device.write_blocks(lba, &sector)?;
+                pos += len;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::{BlockDevice, BlockDeviceAdapter, Fat, FatDeviceAccessible};
+    use crate::error::{FatDeviceError, FatError};
+    use crate::test::{memory_device, MemoryDevice};
+
+    #[derive(Clone, Debug)]
+    struct AlignedDevice {
+        device: MemoryDevice,
+
+        block_size: usize,
+    }
+
+
+    impl BlockDevice for AlignedDevice {
+        fn block_size(&self) -> usize {
+            self.block_size
+        }
+
+
+        fn read_blocks(&self, lba: usize, buff: &mut [u8]) -> Result<(), FatDeviceError> {
+            assert_eq!(buff.len() % self.block_size, 0);
+            self.device.read(buff, lba * self.block_size, buff.len())
+        }
+
+
+        fn write_blocks(&mut self, lba: usize, buff: &[u8]) -> Result<(), FatDeviceError> {
+            assert_eq!(buff.len() % self.block_size, 0);
+            self.device.write(buff, lba * self.block_size)
+        }
+    }
+
+
+    fn adapter(block_size: usize) -> (MemoryDevice, BlockDeviceAdapter<AlignedDevice>) {
+        let device = memory_device();
+        let adapter = BlockDeviceAdapter::new(AlignedDevice {
+            device: device.clone(),
+            block_size,
+        }).unwrap();
+
+        (device, adapter)
+    }
+
+
+    #[test]
+    fn it_read_across_blocks() {
+        let (device, adapter) = adapter(0x200);
+        let mut expected = [0; 0x500];
+        let mut buff = [0; 0x500];
+        device.read(&mut expected, 0x1F0, 0x500).unwrap();
+        adapter.read(&mut buff, 0x1F0, 0x500).unwrap();
+
+        assert_eq!(buff, expected);
+    }
+
+
+    #[test]
+    fn it_write_keeps_neighbour_bytes() {
+        let (device, mut adapter) = adapter(0x200);
+        let mut before = [0; 0x600];
+        device.read(&mut before, 0x102000, 0x600).unwrap();
+
+        adapter.write(&[0xAB; 0x210], 0x102100).unwrap();
+
+        let mut after = [0; 0x600];
+        device.read(&mut after, 0x102000, 0x600).unwrap();
+        assert_eq!(after[..0x100], before[..0x100]);
+        assert!(after[0x100..0x310].iter().all(|b| *b == 0xAB));
+        assert_eq!(after[0x310..], before[0x310..]);
+    }
+
+
+    #[test]
+    fn it_mount_on_block_device() {
+        let (_, adapter) = adapter(0x1000);
+        let fat = Fat::mount(adapter).unwrap();
+        fat.create_file("/WORLD.TXT").unwrap().append(b"world").unwrap();
+
+        let hello = fat.open_file("/HELLO.TXT").unwrap().read_boxed().unwrap();
+        let world = fat.open_file("/WORLD.TXT").unwrap().read_boxed().unwrap();
+        assert_eq!(hello, b"hello\n");
+        assert_eq!(world, b"world");
+    }
+
+
+    #[test]
+    fn it_write_through_clone_while_reading() {
+        let (device, mut adapter) = adapter(0x200);
+        let reader = adapter.clone();
+        adapter.write(b"world", 0x102105).unwrap();
+
+        let mut buff = [0; 5];
+        reader.read(&mut buff, 0x102105, 5).unwrap();
+        assert_eq!(&buff, b"world");
+        device.read(&mut buff, 0x102105, 5).unwrap();
+        assert_eq!(&buff, b"world");
+    }
+
+
+    #[test]
+    fn it_failed_new_with_zero_block_size() {
+        let adapter = BlockDeviceAdapter::new(AlignedDevice {
+            device: memory_device(),
+            block_size: 0,
+        });
+
+        assert!(matches!(adapter, Err(FatError::InvalidBlockSize)));
+    }
+}
@@ -0,0 +1,363 @@
+use core::ops::Range;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use spin::Mutex;
+
+use crate::bpb::params::BiosParameterBlock;
+use crate::device::FatDeviceAccessible;
+use crate::error::{FatDeviceError, FatResult};
+
+pub const CACHE_BLOCK_SIZE: usize = 0x200;
+
+
+#[derive(Clone)]
+pub struct CacheBlock {
+    block_no: Option<usize>,
+
+    dirty: bool,
+
+    last_used: usize,
+
+    data: [u8; CACHE_BLOCK_SIZE],
+}
+
+
+impl CacheBlock {
+    pub const EMPTY: CacheBlock = CacheBlock {
+        block_no: None,
+        dirty: false,
+        last_used: 0,
+        data: [0; CACHE_BLOCK_SIZE],
+    };
+}
+
+
+/// Writes stay in memory until [`BlockCache::flush`] or [`BlockCache::into_inner`];
+/// dropping the cache without calling either discards them.
+pub struct BlockCache<D, S> {
+    state: Mutex<CacheState<D, S>>,
+}
+
+
+impl<D, S> BlockCache<D, S>
+    where D: FatDeviceAccessible,
+          S: AsMut<[CacheBlock]>
+{
+    pub fn with_blocks(device: D, mut blocks: S, fat_blocks: usize) -> FatResult<BlockCache<D, S>> {
+        let params = BiosParameterBlock::new(&device)?;
+        let fat_start = params.fat_offset() / CACHE_BLOCK_SIZE;
+        let fat_end = (params.fat_offset() + params.fat_count() * params.fat_bytes()).div_ceil(CACHE_BLOCK_SIZE);
+        let fat_blocks = fat_blocks.min(blocks.as_mut().len().saturating_sub(1));
+
+        Ok(Self {
+            state: Mutex::new(CacheState {
+                device,
+                blocks,
+                fat_blocks,
+                fat_region: fat_start..fat_end,
+                tick: 0,
+            })
+        })
+    }
+
+
+    #[inline]
+    pub fn flush(&self) -> FatResult {
+        Ok(self.state.lock().flush()?)
+    }
+
+
+    pub fn into_inner(self) -> FatResult<D> {
+        let mut state = self.state.into_inner();
+        state.flush()?;
+        Ok(state.device)
+    }
+}
+
+
+#[cfg(feature = "alloc")]
+impl<D> BlockCache<D, Vec<CacheBlock>>
+    where D: FatDeviceAccessible
+{
+    pub fn with_capacity(device: D, capacity: usize, fat_blocks: usize) -> FatResult<BlockCache<D, Vec<CacheBlock>>> {
+        Self::with_blocks(device, alloc::vec![CacheBlock::EMPTY; capacity], fat_blocks)
+    }
+}
+
+
+impl<D, S> FatDeviceAccessible for &BlockCache<D, S>
+    where D: FatDeviceAccessible,
+          S: AsMut<[CacheBlock]>
+{
+    fn read(&self, buff: &mut [u8], offset: usize, bytes: usize) -> Result<(), FatDeviceError> {
+        self.state.lock().read(&mut buff[..bytes], offset)
+    }
+
+
+    fn write(&mut self, buff: &[u8], offset: usize) -> Result<(), FatDeviceError> {
+        self.state.lock().write(buff, offset)
+    }
+
+
+    fn flush(&mut self) -> Result<(), FatDeviceError> {
+        self.state.lock().flush()
+    }
+}
+
+
+struct CacheState<D, S> {
+    device: D,
+
+    blocks: S,
+
+    fat_blocks: usize,
+
+    fat_region: Range<usize>,
+
+    tick: usize,
+}
+
+
+impl<D, S> CacheState<D, S>
+    where D: FatDeviceAccessible,
+          S: AsMut<[CacheBlock]>
+{
+    fn read(&mut self, buff: &mut [u8], offset: usize) -> Result<(), FatDeviceError> {
+        let mut pos = 0;
+        while pos < buff.len() {
+            let head = (offset + pos) % CACHE_BLOCK_SIZE;
+            let len = (CACHE_BLOCK_SIZE - head).min(buff.len() - pos);
+
+            let block = self.block((offset + pos) / CACHE_BLOCK_SIZE, true)?;
+            buff[pos..(pos + len)].copy_from_slice(&block.data[head..(head + len)]);
+            pos += len;
+        }
+
+        Ok(())
+    }
+
+
+    fn write(&mut self, buff: &[u8], offset: usize) -> Result<(), FatDeviceError> {
+        let mut pos = 0;
+        while pos < buff.len() {
+            let head = (offset + pos) % CACHE_BLOCK_SIZE;
+            let len = (CACHE_BLOCK_SIZE - head).min(buff.len() - pos);
+
+            let overwrites_block = len == CACHE_BLOCK_SIZE;
+            let block = self.block((offset + pos) / CACHE_BLOCK_SIZE, !overwrites_block)?;
+            block.data[head..(head + len)].copy_from_slice(&buff[pos..(pos + len)]);
+            block.dirty = true;
+            pos += len;
+        }
+
+        Ok(())
+    }
+
+
+    fn flush(&mut self) -> Result<(), FatDeviceError> {
+        loop {
+            let next = self
+                .blocks
+                .as_mut()
+                .iter_mut()
+                .filter(|block| block.dirty)
+                .min_by_key(|block| block.block_no);
+
+            match next {
+                Some(block) => {
+                    if let Some(block_no) = block.block_no {
+                        self.device.write(&block.data, block_no * CACHE_BLOCK_SIZE)?;
+                    }
+                    block.dirty = false;
+                }
+                None => return self.device.flush()
+            }
+        }
+    }
+
+
+    /// `load` can be false when the caller overwrites the whole block, which saves the device read on a miss.
+    fn block(&mut self, block_no: usize, load: bool) -> Result<&mut CacheBlock, FatDeviceError> {
+        self.tick += 1;
+        let range = if self.fat_region.contains(&block_no) && 0 < self.fat_blocks {
+            0..self.fat_blocks
+        } else {
+            self.fat_blocks..self.blocks.as_mut().len()
+        };
+        let blocks = &mut self.blocks.as_mut()[range];
+
+        let index = match blocks.iter().position(|block| block.block_no == Some(block_no)) {
+            Some(index) => index,
+            None => {
+                let index = blocks
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, block)| block.block_no.map(|_| block.last_used))
+                    .map(|(index, _)| index)
+                    .ok_or(FatDeviceError::StatusCode(-1))?;
+
+                let victim = &mut blocks[index];
+                if let (true, Some(victim_no)) = (victim.dirty, victim.block_no) {
+                    self.device.write(&victim.data, victim_no * CACHE_BLOCK_SIZE)?;
+                }
+
+                victim.block_no = None;
+                victim.dirty = false;
+                if load {
+                    self.device.read(&mut victim.data, block_no * CACHE_BLOCK_SIZE, CACHE_BLOCK_SIZE)?;
+                }
+                victim.block_no = Some(block_no);
+                index
+            }
+        };
+
+        let block = &mut blocks[index];
+        block.last_used = self.tick;
+        Ok(block)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use alloc::rc::Rc;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
+
+    use crate::{Fat, FatDeviceAccessible};
+    use crate::device::cache::{BlockCache, CACHE_BLOCK_SIZE, CacheBlock};
+    use crate::error::FatDeviceError;
+    use crate::test::{memory_device, MemoryDevice};
+
+    #[derive(Clone, Debug)]
+    struct RecordingDevice {
+        device: MemoryDevice,
+
+        reads: Rc<RefCell<Vec<usize>>>,
+
+        writes: Rc<RefCell<Vec<usize>>>,
+    }
+
+
+    impl FatDeviceAccessible for RecordingDevice {
+        fn read(&self, buff: &mut [u8], offset: usize, bytes: usize) -> Result<(), FatDeviceError> {
+            self.reads.borrow_mut().push(offset);
+            self.device.read(buff, offset, bytes)
+        }
+
+
+        fn write(&mut self, buff: &[u8], offset: usize) -> Result<(), FatDeviceError> {
+            self.writes.borrow_mut().push(offset);
+            self.device.write(buff, offset)
+        }
+    }
+
+
+    fn recording_device() -> RecordingDevice {
+        RecordingDevice {
+            device: memory_device(),
+            reads: Rc::new(RefCell::new(Vec::new())),
+            writes: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+
+    #[test]
+    fn it_read_from_cache() {
+        let device = recording_device();
+        let cache = BlockCache::with_blocks(device.clone(), [CacheBlock::EMPTY; 4], 1).unwrap();
+        device.reads.borrow_mut().clear();
+
+        let mut buff = [0; 6];
+        (&cache).read(&mut buff, 0x102400, 6).unwrap();
+        (&cache).read(&mut buff, 0x102400, 6).unwrap();
+
+        assert_eq!(&buff, b"hello\n");
+        assert_eq!(*device.reads.borrow(), [0x102400]);
+    }
+
+
+    #[test]
+    fn it_write_back_on_flush() {
+        let device = recording_device();
+        let cache = BlockCache::with_capacity(device.clone(), 4, 1).unwrap();
+        (&cache).write(b"HELLO", 0x102400).unwrap();
+        (&cache).write(&[0xFF; 4], 0x4010).unwrap();
+
+        let mut buff = [0; 5];
+        device.read(&mut buff, 0x102400, 5).unwrap();
+        assert_eq!(&buff, b"hello");
+        assert!(device.writes.borrow().is_empty());
+
+        cache.flush().unwrap();
+        device.read(&mut buff, 0x102400, 5).unwrap();
+        assert_eq!(&buff, b"HELLO");
+        assert_eq!(*device.writes.borrow(), [0x4000, 0x102400]);
+    }
+
+
+    #[test]
+    fn it_skip_read_for_full_block_write() {
+        let device = recording_device();
+        let cache = BlockCache::with_capacity(device.clone(), 4, 1).unwrap();
+        device.reads.borrow_mut().clear();
+
+        (&cache).write(&[0xAB; CACHE_BLOCK_SIZE + 1], 0x102400).unwrap();
+        cache.flush().unwrap();
+        assert_eq!(*device.reads.borrow(), [0x102600]);
+
+        let mut buff = [0; CACHE_BLOCK_SIZE + 2];
+        device.read(&mut buff, 0x102400, CACHE_BLOCK_SIZE + 2).unwrap();
+        assert!(buff[..(CACHE_BLOCK_SIZE + 1)].iter().all(|b| *b == 0xAB));
+        assert_eq!(buff[CACHE_BLOCK_SIZE + 1], 0);
+    }
+
+
+    #[test]
+    fn it_write_back_evicted_block() {
+        let device = recording_device();
+        let cache = BlockCache::with_blocks(device.clone(), [CacheBlock::EMPTY; 2], 1).unwrap();
+        (&cache).write(b"HELLO", 0x102400).unwrap();
+        (&cache).read(&mut [0; 1], 0x102800, 1).unwrap();
+
+        let mut buff = [0; 5];
+        device.read(&mut buff, 0x102400, 5).unwrap();
+        assert_eq!(&buff, b"HELLO");
+    }
+
+
+    #[test]
+    fn it_keep_fat_blocks_while_reading_data() {
+        let device = recording_device();
+        let cache = BlockCache::with_blocks(device.clone(), [CacheBlock::EMPTY; 3], 1).unwrap();
+        (&cache).read(&mut [0; 4], 0x400C, 4).unwrap();
+        for block_no in 0..8 {
+            (&cache).read(&mut [0; 1], 0x102000 + block_no * CACHE_BLOCK_SIZE, 1).unwrap();
+        }
+
+        device.reads.borrow_mut().clear();
+        (&cache).read(&mut [0; 4], 0x400C, 4).unwrap();
+        assert!(device.reads.borrow().is_empty());
+    }
+
+
+    #[test]
+    fn it_flush_fat_on_cache() {
+        let device = memory_device();
+        let cache = BlockCache::with_capacity(device.clone(), 16, 4).unwrap();
+        let mut fat = Fat::mount(&cache).unwrap();
+        fat.create_file("/WORLD.TXT").unwrap().append(b"world").unwrap();
+
+        assert!(Fat::mount(device.clone()).unwrap().open("/WORLD.TXT").is_err());
+
+        fat.flush().unwrap();
+        let world = Fat::mount(device)
+            .unwrap()
+            .open_file("/WORLD.TXT")
+            .unwrap()
+            .read_boxed()
+            .unwrap();
+        assert_eq!(world, b"world");
+    }
+}
@@ -0,0 +1,104 @@
+use crate::device::FatDeviceAccessible;
+use crate::error::FatDeviceError;
+
+#[derive(Clone, Debug)]
+pub struct OffsetDevice<D> {
+    device: D,
+
+    offset: usize,
+
+    len: usize,
+}
+
+
+impl<D> OffsetDevice<D> {
+    #[inline]
+    pub const fn new(device: D, offset: usize, len: usize) -> OffsetDevice<D> {
+        Self {
+            device,
+            offset,
+            len,
+        }
+    }
+
+
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+
+    fn check_range(&self, offset: usize, bytes: usize) -> Result<(), FatDeviceError> {
+        match offset.checked_add(bytes) {
+            Some(end) if end <= self.len => Ok(()),
+            _ => Err(FatDeviceError::OutOfRange(offset))
+        }
+    }
+}
+
+
+impl<D> FatDeviceAccessible for OffsetDevice<D>
+    where D: FatDeviceAccessible
+{
+    fn read(&self, buff: &mut [u8], offset: usize, bytes: usize) -> Result<(), FatDeviceError> {
+        self.check_range(offset, bytes)?;
+        self.device.read(buff, self.offset + offset, bytes)
+    }
+
+
+    fn write(&mut self, buff: &[u8], offset: usize) -> Result<(), FatDeviceError> {
+        self.check_range(offset, buff.len())?;
+        self.device.write(buff, self.offset + offset)
+    }
+
+
+    #[inline]
+    fn flush(&mut self) -> Result<(), FatDeviceError> {
+        self.device.flush()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::device::offset::OffsetDevice;
+    use crate::error::FatDeviceError;
+    use crate::FatDeviceAccessible;
+    use crate::test::memory_device;
+
+    #[test]
+    fn it_read_with_offset() {
+        let device = OffsetDevice::new(memory_device(), 0x102400, 0x400);
+        let mut buff = [0; 6];
+        device.read(&mut buff, 0, 6).unwrap();
+
+        assert_eq!(&buff, b"hello\n");
+    }
+
+
+    #[test]
+    fn it_failed_access_out_of_range() {
+        let mut device = OffsetDevice::new(memory_device(), 0x102400, 0x400);
+
+        assert_eq!(device.read(&mut [0; 2], 0x3FF, 2), Err(FatDeviceError::OutOfRange(0x3FF)));
+        assert_eq!(device.write(&[0; 2], 0x400), Err(FatDeviceError::OutOfRange(0x400)));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod entry;
 pub mod data;
-
+pub mod path;
+pub mod name;
 
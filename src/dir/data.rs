@@ -1,9 +1,17 @@
+use alloc::string::ToString;
+
 use crate::bpb::BpbReadable;
 use crate::dir::data::dir::DirEntries;
 use crate::dir::data::file::RegularFile;
-use crate::dir::entry::short::ShortDirEntryReadable;
+use crate::dir::entry::{Attribute, DirEntry};
+use crate::dir::entry::base::DirEntryReadable;
+use crate::dir::entry::long::LongNameBuilder;
+use crate::dir::entry::short::{ShortDirEntry, ShortDirEntryReadable, ShortDirEntryWritable};
+use crate::dir::name::{eq_long_name, eq_short_name};
+use crate::dir::path;
 use crate::error::{FatError, FatResult};
 use crate::FatDeviceAccessible;
+use crate::time::{FatDate, FatDateTime, FatTime};
 
 pub mod dir;
 pub mod file;
@@ -12,6 +20,10 @@ pub struct DataEntries<D>
     where D: FatDeviceAccessible + Clone + BpbReadable
 {
     dir_entries: DirEntries<D>,
+
+    long_name: LongNameBuilder,
+
+    error: Option<FatError>,
 }
 
 
@@ -20,31 +32,77 @@ impl<D> DataEntries<D>
 {
     pub fn new(dir_entries: DirEntries<D>) -> DataEntries<D> {
         Self {
-            dir_entries
+            dir_entries,
+            long_name: LongNameBuilder::new(),
+            error: None,
+        }
+    }
+
+
+    pub fn open(&self, path: &str) -> FatResult<Data<D>> {
+        let mut data = Data::Dir(self.dir_entries.clone());
+
+        for name in path::components(path) {
+            let dir = data
+                .into_dir()
+                .map_err(|_| FatError::NotfoundFile(name.to_string()))?;
+
+            data = match name {
+                "." => Data::Dir(dir),
+                ".." if dir.is_root()? => Data::Dir(dir),
+                _ => dir
+                    .into_data_entries()
+                    .try_find(name)?
+                    .ok_or(FatError::NotfoundFile(name.to_string()))?
+            };
         }
+
+        Ok(data)
     }
 
 
     pub fn find(&mut self, file_name: &str) -> Option<Data<D>> {
-        let data = self.next()?;
-        let name = data.name().ok();
+        Iterator::find(self, |data| data.is_named(file_name))
+    }
 
-        if name
-            .map(|name| name.to_str().map(|name| name == file_name).unwrap_or(false))
-            .unwrap_or(false)
-        {
+
+    /// Like [`DataEntries::find`], but fails if the directory could not be read to the end.
+    pub fn try_find(&mut self, file_name: &str) -> FatResult<Option<Data<D>>> {
+        let data = self.find(file_name);
+        match self.take_error() {
+            Some(e) => Err(e),
+            None => Ok(data)
+        }
+    }
+
+
+    /// Returns the error that stopped the iteration, if any.
+    #[inline]
+    pub fn take_error(&mut self) -> Option<FatError> {
+        self.error.take()
+    }
+
+
+    pub fn find_recursive(&mut self, file_name: &str) -> Option<Data<D>> {
+        let data = self.next()?;
+        if data.is_named(file_name) {
             return Some(data);
         }
 
+        let name = data.name().ok();
+        let name = name
+            .as_ref()
+            .and_then(|name| name.to_str().ok());
+
         match data {
-            Data::RegularFile(_) => { self.find(file_name) }
-            Data::Dir(dir) => {
-                if let Some(data) = dir.into_data_entries().find(file_name) {
+            Data::Dir(dir) if !matches!(name, Some("." | "..")) => {
+                if let Some(data) = dir.into_data_entries().find_recursive(file_name) {
                     Some(data)
                 }else{
-                    self.find(file_name)
+                    self.find_recursive(file_name)
                 }
             }
+            _ => { self.find_recursive(file_name) }
         }
     }
 }
@@ -56,17 +114,37 @@ impl<D> Iterator for DataEntries<D>
     type Item = Data<D>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let entry = self
-            .dir_entries
-            .next()?;
-
-        if let Ok(data) = entry
-            .into_detail()
-            .and_then(|detail| detail.into_short())
-            .and_then(|short| short.data()) {
-            Some(data)
-        } else {
-            self.next()
+        loop {
+            let detail = self
+                .dir_entries
+                .next()?
+                .and_then(|entry| entry.into_detail());
+
+            match detail {
+                Ok(DirEntry::Long(long)) => {
+                    if self.long_name.push(&long).is_err() {
+                        self.long_name.reset();
+                    }
+                }
+                Ok(DirEntry::Short(short)) => {
+                    let long_name = short
+                        .name_checksum()
+                        .ok()
+                        .and_then(|checksum| self.long_name.build(checksum));
+
+                    match short.with_long_name(long_name).data(self.dir_entries.options().clone()) {
+                        Ok(data) => return Some(data),
+                        Err(e) => {
+                            self.error = Some(e);
+                            return None;
+                        }
+                    }
+                }
+                Err(e) => {
+                    self.error = Some(e);
+                    return None;
+                }
+            }
         }
     }
 }
@@ -89,6 +167,14 @@ impl<D> ShortDirEntryReadable for Data<D> where D: FatDeviceAccessible + BpbRead
     }
 
 
+    fn nt_reserved(&self) -> FatResult<u8> {
+        match self {
+            Self::RegularFile(file) => { file.nt_reserved() }
+            Self::Dir(dir) => { dir.nt_reserved() }
+        }
+    }
+
+
     fn first_cluster_no_hi(&self) -> FatResult<u16> {
         match self {
             Self::RegularFile(file) => { file.first_cluster_no_hi() }
@@ -111,10 +197,171 @@ impl<D> ShortDirEntryReadable for Data<D> where D: FatDeviceAccessible + BpbRead
             Self::Dir(dir) => { dir.file_size() }
         }
     }
+
+
+    fn create_time_tenth(&self) -> FatResult<u8> {
+        match self {
+            Self::RegularFile(file) => { file.create_time_tenth() }
+            Self::Dir(dir) => { dir.create_time_tenth() }
+        }
+    }
+
+
+    fn create_time(&self) -> FatResult<FatTime> {
+        match self {
+            Self::RegularFile(file) => { file.create_time() }
+            Self::Dir(dir) => { dir.create_time() }
+        }
+    }
+
+
+    fn create_date(&self) -> FatResult<FatDate> {
+        match self {
+            Self::RegularFile(file) => { file.create_date() }
+            Self::Dir(dir) => { dir.create_date() }
+        }
+    }
+
+
+    fn last_access_date(&self) -> FatResult<FatDate> {
+        match self {
+            Self::RegularFile(file) => { file.last_access_date() }
+            Self::Dir(dir) => { dir.last_access_date() }
+        }
+    }
+
+
+    fn write_time(&self) -> FatResult<FatTime> {
+        match self {
+            Self::RegularFile(file) => { file.write_time() }
+            Self::Dir(dir) => { dir.write_time() }
+        }
+    }
+
+
+    fn write_date(&self) -> FatResult<FatDate> {
+        match self {
+            Self::RegularFile(file) => { file.write_date() }
+            Self::Dir(dir) => { dir.write_date() }
+        }
+    }
+}
+
+
+impl<D> DirEntryReadable for Data<D> where D: FatDeviceAccessible + BpbReadable + Clone {
+    fn status_raw(&self) -> FatResult<u8> {
+        match self {
+            Self::RegularFile(file) => { file.status_raw() }
+            Self::Dir(dir) => { dir.status_raw() }
+        }
+    }
+
+
+    fn attribute_raw(&self) -> FatResult<u8> {
+        match self {
+            Self::RegularFile(file) => { file.attribute_raw() }
+            Self::Dir(dir) => { dir.attribute_raw() }
+        }
+    }
+}
+
+
+impl<D> ShortDirEntryWritable for Data<D> where D: FatDeviceAccessible + BpbReadable + Clone {
+    fn write_first_cluster_no(&mut self, cluster_no: u32) -> FatResult {
+        match self {
+            Self::RegularFile(file) => { file.write_first_cluster_no(cluster_no) }
+            Self::Dir(dir) => { dir.write_first_cluster_no(cluster_no) }
+        }
+    }
+
+
+    fn write_file_size(&mut self, file_size: u32) -> FatResult {
+        match self {
+            Self::RegularFile(file) => { file.write_file_size(file_size) }
+            Self::Dir(dir) => { dir.write_file_size(file_size) }
+        }
+    }
+
+
+    fn write_created(&mut self, created: FatDateTime) -> FatResult {
+        match self {
+            Self::RegularFile(file) => { file.write_created(created) }
+            Self::Dir(dir) => { dir.write_created(created) }
+        }
+    }
+
+
+    fn write_modified(&mut self, modified: FatDateTime) -> FatResult {
+        match self {
+            Self::RegularFile(file) => { file.write_modified(modified) }
+            Self::Dir(dir) => { dir.write_modified(modified) }
+        }
+    }
+
+
+    fn write_last_access_date(&mut self, date: FatDate) -> FatResult {
+        match self {
+            Self::RegularFile(file) => { file.write_last_access_date(date) }
+            Self::Dir(dir) => { dir.write_last_access_date(date) }
+        }
+    }
+
+
+    fn write_attribute(&mut self, attribute: Attribute) -> FatResult {
+        match self {
+            Self::RegularFile(file) => { file.write_attribute(attribute) }
+            Self::Dir(dir) => { dir.write_attribute(attribute) }
+        }
+    }
+
+
+    fn set_attribute(&mut self, flags: Attribute, enabled: bool) -> FatResult {
+        match self {
+            Self::RegularFile(file) => { file.set_attribute(flags, enabled) }
+            Self::Dir(dir) => { dir.set_attribute(flags, enabled) }
+        }
+    }
+
+
+    fn mark_deleted(&mut self) -> FatResult {
+        match self {
+            Self::RegularFile(file) => { file.mark_deleted() }
+            Self::Dir(dir) => { dir.mark_deleted() }
+        }
+    }
 }
 
 
 impl<D> Data<D> where D: FatDeviceAccessible + Clone + BpbReadable {
+    #[inline]
+    pub fn long_name(&self) -> Option<&str> {
+        match self {
+            Self::RegularFile(file) => { file.long_name() }
+            Self::Dir(dir) => { dir.long_name() }
+        }
+    }
+
+
+    #[inline]
+    pub(crate) fn entry(&self) -> &ShortDirEntry<D> {
+        match self {
+            Self::RegularFile(file) => { &file.entry }
+            Self::Dir(dir) => { dir.entry() }
+        }
+    }
+
+
+    pub fn is_named(&self, name: &str) -> bool {
+        self.long_name()
+            .map(|long_name| eq_long_name(long_name, name))
+            .unwrap_or(false)
+            || self
+            .name()
+            .map(|short_name| eq_short_name(short_name.as_bytes(), name))
+            .unwrap_or(false)
+    }
+
+
     pub fn into_dir(self) -> FatResult<DirEntries<D>> {
         if let Self::Dir(dir) = self {
             Ok(dir)
@@ -138,3 +385,118 @@ impl<D> Data<D> where D: FatDeviceAccessible + Clone + BpbReadable {
         matches!(self, Self::RegularFile(_))
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{Fat, FatDeviceAccessible};
+    use crate::dir::entry::base::DirEntryReadable;
+    use crate::dir::entry::short::ShortDirEntryReadable;
+    use crate::error::FatError;
+    use crate::test::{memory_device, MemoryDevice, open_fat32_file};
+
+    fn fat_with_readme_in_test_dir() -> Fat<MemoryDevice> {
+        let mut device = memory_device();
+        let mut entry = [0; 0x20];
+        device.read(&mut entry, 0x102040, 0x20).unwrap();
+        entry[..11].copy_from_slice(b"README  TXT");
+        device.write(&entry, 0x102000 + 3 * 0x400 + 0x40).unwrap();
+
+        Fat::mount(device).unwrap()
+    }
+
+
+    #[test]
+    fn it_open_absolute_path() {
+        let fat = open_fat32_file();
+        let hello_txt = fat.open("/HELLO.TXT").unwrap();
+        assert_eq!(hello_txt.name().unwrap().to_str().unwrap(), "HELLO.TXT");
+    }
+
+
+    #[test]
+    fn it_open_root() {
+        let fat = open_fat32_file();
+        assert!(fat.open("/").unwrap().into_dir().unwrap().is_root().unwrap());
+    }
+
+
+    #[test]
+    fn it_open_nested_file() {
+        let fat = fat_with_readme_in_test_dir();
+        let buff = fat
+            .open_file("/TEST/README.TXT")
+            .unwrap()
+            .read_boxed()
+            .unwrap();
+
+        assert_eq!(&buff, b"hello\n");
+    }
+
+
+    #[test]
+    fn it_not_found_nested_file_in_root() {
+        let fat = fat_with_readme_in_test_dir();
+        assert_eq!(fat.open("README.TXT").err(), Some(FatError::NotfoundFile("README.TXT".to_string())));
+    }
+
+
+    #[test]
+    fn it_find_recursive_nested_file() {
+        let fat = fat_with_readme_in_test_dir();
+        let readme_txt = fat
+            .root_dir()
+            .unwrap()
+            .find_recursive("README.TXT");
+
+        assert!(readme_txt.is_some());
+    }
+
+
+    #[test]
+    fn it_open_with_dot_and_dot_dot() {
+        let fat = open_fat32_file();
+        let hello_txt = fat.open("/TEST/./../TEST/../HELLO.TXT");
+        assert!(hello_txt.is_ok());
+    }
+
+
+    #[test]
+    fn it_dot_dot_of_root_is_root() {
+        let fat = open_fat32_file();
+        assert!(fat.open("../HELLO.TXT").is_ok());
+    }
+
+
+    #[test]
+    fn it_not_found_component_under_regular_file() {
+        let fat = open_fat32_file();
+        assert_eq!(fat.open("/HELLO.TXT/TEST").err(), Some(FatError::NotfoundFile("TEST".to_string())));
+    }
+
+
+    #[test]
+    fn it_open_ignore_case() {
+        let fat = fat_with_readme_in_test_dir();
+        assert!(fat.open_file("/test/readme.txt").is_ok());
+        assert!(fat.open_file("/Test/Hello.TXT").is_err());
+        assert!(fat.open_file("/HeLLo.txt").is_ok());
+    }
+
+
+    #[test]
+    fn it_open_entries_with_combined_attributes() {
+        let mut device = memory_device();
+        device.write(&[0x23], 0x102040 + 11).unwrap();
+        device.write(&[0x32], 0x102080 + 11).unwrap();
+        let fat = Fat::mount(device).unwrap();
+
+        let hello = fat.open("/HELLO.TXT").unwrap();
+        assert!(hello.attribute().unwrap().is_read_only());
+        assert!(hello.into_regular_file().is_ok());
+        assert!(fat.open("/TEST").unwrap().into_dir().is_ok());
+        assert_eq!(fat.remove_file("/HELLO.TXT").err().unwrap(), FatError::ReadOnly("HELLO.TXT".to_string()));
+    }
+}
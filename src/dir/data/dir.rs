@@ -1,14 +1,30 @@
 use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
 use core::fmt::{Debug, Formatter};
 
 use auto_delegate::Delegate;
 
 use crate::bpb::BpbReadable;
-use crate::dir::data::DataEntries;
+use crate::dir::data::{Data, DataEntries};
+use crate::dir::data::file::RegularFile;
+use crate::dir::entry::{Attribute, EntryStatus};
 use crate::dir::entry::base::{BaseDirEntry, DirEntryReadable};
-use crate::dir::entry::EntryStatus;
-use crate::dir::entry::short::ShortDirEntry;
+use crate::dir::entry::long::{long_entries_buff, LongName, short_name_checksum};
+use crate::dir::entry::short::{new_short_entry_buff, ShortDirEntry, ShortDirEntryReadable, ShortDirEntryWritable};
+use crate::dir::name::{basis_name, fit_short_name, is_valid_long_name, trim_long_name, with_numeric_tail};
+use crate::error::{FatError, FatResult};
 use crate::FatDeviceAccessible;
+use crate::options::MountOptions;
+use crate::table::chain::ClusterChain;
+use crate::table::FatTable;
+use crate::time::{FatDate, FatDateTime};
+
+const DOT_NAME: &[u8; 11] = b".          ";
+
+const DOT_DOT_NAME: &[u8; 11] = b"..         ";
+
 
 #[derive(Delegate)]
 pub struct DirEntries<D>
@@ -17,29 +33,44 @@ pub struct DirEntries<D>
     #[to(DirEntryReadable, ShortDirEntryReadable, BpbReadable, FatDeviceAccessible)]
     entry: ShortDirEntry<D>,
 
-    base_offset: usize,
+    first_cluster_no: usize,
+
+    chain: ClusterChain<D>,
+
+    cluster_offset: Option<usize>,
+
+    index: usize,
 
-    offset: usize
+    end: bool,
+
+    options: MountOptions,
 }
 
 
 impl<D> DirEntries<D>
     where D: FatDeviceAccessible + Clone + BpbReadable
 {
-    pub fn root(bpb: D, base_offset: usize) -> DirEntries<D> {
-        let entry = ShortDirEntry::new(BaseDirEntry::new(bpb, base_offset));
-        Self {
-            entry,
-            base_offset,
-            offset: base_offset
-        }
+    pub fn root(bpb: D, options: MountOptions) -> FatResult<DirEntries<D>> {
+        let root_cluster_no = bpb.root_dir_cluster_no()?;
+        let offset = match bpb.root_dir_region()? {
+            Some(region) => region.start,
+            None => bpb.data_cluster_offset_at(root_cluster_no)?
+        };
+        let entry = ShortDirEntry::new(BaseDirEntry::new(bpb, offset));
+        Ok(Self::from_entry(entry, root_cluster_no, options))
     }
 
-    pub fn from_entry(entry: ShortDirEntry<D>, base_offset: usize) -> DirEntries<D> {
+
+    pub fn from_entry(entry: ShortDirEntry<D>, first_cluster_no: usize, options: MountOptions) -> DirEntries<D> {
+        let chain = FatTable::new(entry.base.bpb.clone()).chain(first_cluster_no);
         Self {
             entry,
-            base_offset,
-            offset: base_offset
+            first_cluster_no,
+            chain,
+            cluster_offset: None,
+            index: 0,
+            end: false,
+            options,
         }
     }
 
@@ -51,23 +82,393 @@ impl<D> DirEntries<D>
 
 
     #[inline]
-    fn offset(&self, offset: usize) -> usize {
-        self.offset + offset
+    pub fn long_name(&self) -> Option<&str> {
+        self.entry.long_name()
     }
 
 
-    fn find_next(&mut self, offset: usize) -> Option<BaseDirEntry<D>> {
-        let entry = BaseDirEntry::new(self.entry.base.bpb.clone(), offset);
+    #[inline]
+    pub fn is_root(&self) -> FatResult<bool> {
+        Ok(self.first_cluster_no == self.entry.root_dir_cluster_no()?)
+    }
+
+
+    #[inline]
+    pub(crate) fn entry(&self) -> &ShortDirEntry<D> {
+        &self.entry
+    }
+
+
+    #[inline]
+    pub(crate) fn options(&self) -> &MountOptions {
+        &self.options
+    }
 
-        if let Some(status) = entry.status() {
-            match status {
-                EntryStatus::End => None,
-                _ => self.find_next(offset + 0x20)
+
+    /// The root directory's `entry` is only a handle on the root region, not an entry of its own.
+    fn own_entry_mut(&mut self) -> FatResult<&mut ShortDirEntry<D>> {
+        if self.is_root()? {
+            return Err(FatError::RootDirHasNoEntry);
+        }
+
+        Ok(&mut self.entry)
+    }
+
+
+    pub fn create_file(&mut self, name: &str) -> FatResult<RegularFile<D>> {
+        Ok(RegularFile::new(self.create_entry(name, Attribute::ARCHIVE, 0)?, self.options.clone()))
+    }
+
+
+    pub fn create_dir(&mut self, name: &str) -> FatResult<DirEntries<D>> {
+        let mut bpb = self.entry.base.bpb.clone();
+        let mut table = FatTable::new(bpb.clone());
+        let cluster_no = table.allocate()?;
+        let parent_cluster_no = if self.is_root()? { 0 } else { self.first_cluster_no as u32 };
+
+        let now = self.options.now();
+        let mut buff = vec![0; bpb.bytes_per_cluster()?];
+        buff[..0x20].copy_from_slice(&new_short_entry_buff(DOT_NAME, Attribute::DIR.bits(), 0, cluster_no as u32, now));
+        buff[0x20..0x40].copy_from_slice(&new_short_entry_buff(DOT_DOT_NAME, Attribute::DIR.bits(), 0, parent_cluster_no, now));
+
+        let entry = bpb
+            .data_cluster_offset_at(cluster_no)
+            .and_then(|offset| Ok(bpb.write(&buff, offset)?))
+            .and_then(|_| self.create_entry(name, Attribute::DIR, cluster_no as u32));
+
+        match entry {
+            Ok(entry) => Ok(DirEntries::from_entry(entry, cluster_no, self.options.clone())),
+            Err(e) => {
+                table.free_clusters(&[cluster_no])?;
+                Err(e)
             }
+        }
+    }
+
+
+    pub fn remove(mut self) -> FatResult {
+        self.ensure_removable()?;
+        FatTable::new(self.entry.base.bpb.clone()).free_chain(self.first_cluster_no)?;
+        self.entry.mark_deleted()
+    }
+
+
+    pub(crate) fn ensure_removable(&self) -> FatResult {
+        let name = || self.entry.name().map(|name| name.to_string_lossy().into_owned());
+        if self.is_root()? {
+            return Err(FatError::InvalidFileName(name()?));
+        }
+
+        for entry in self.clone() {
+            let entry = entry?;
+            if entry.attribute()?.is_long_name() {
+                continue;
+            }
+
+            let name_buff = ShortDirEntry::new(entry).name_buff()?;
+            if &name_buff != DOT_NAME && &name_buff != DOT_DOT_NAME {
+                return Err(FatError::DirectoryNotEmpty(name()?));
+            }
+        }
+
+        Ok(())
+    }
+
+
+    pub fn rename_into(&mut self, source: Data<D>, name: &str, overwrite: bool) -> FatResult<Data<D>> {
+        if !is_valid_long_name(name) {
+            return Err(FatError::InvalidFileName(name.to_string()));
+        }
+        let name = trim_long_name(name);
+
+        let source_name = source.name()?.to_string_lossy().into_owned();
+        if source.attribute()?.is_volume_label() {
+            return Err(FatError::IsVolumeLabel(source_name));
+        }
+        if let Data::Dir(dir) = &source {
+            if dir.is_root()? || matches!(source_name.as_str(), "." | "..") {
+                return Err(FatError::InvalidFileName(source_name));
+            }
+            if self.is_inside_of(dir.first_cluster_no)? {
+                return Err(FatError::MoveIntoItself(source_name));
+            }
+        }
+
+        let mut entry = source.entry().clone();
+        let mut buff = [0; 0x20];
+        entry.base.bpb.read(&mut buff, entry.base.offset, 0x20)?;
+
+        let target = match self.clone().into_data_entries().try_find(name)? {
+            Some(target) if target.entry().base.offset == entry.base.offset => None,
+            Some(target) if overwrite => {
+                match (&target, &source) {
+                    (Data::RegularFile(file), Data::RegularFile(_)) => file.ensure_removable()?,
+                    (Data::Dir(dir), Data::Dir(_)) => dir.ensure_removable()?,
+                    (Data::Dir(_), _) => return Err(FatError::IsDirectory(name.to_string())),
+                    (Data::RegularFile(_), _) => return Err(FatError::NotDirectory(name.to_string()))
+                }
+                Some(target)
+            }
+            Some(_) => return Err(FatError::AlreadyExists(name.to_string())),
+            None => None
+        };
+
+        // The target is only removed once the new entry is written, so a failed insert loses nothing.
+        let replacing = target.as_ref().map_or(entry.base.offset, |target| target.entry().base.offset);
+        let data = self.insert_entry(name, buff, Some(replacing))?.data(self.options.clone())?;
+        match target {
+            Some(Data::RegularFile(file)) => file.remove()?,
+            Some(Data::Dir(dir)) => dir.remove()?,
+            None => {}
+        }
+        entry.mark_deleted()?;
+        if let Data::Dir(dir) = &data {
+            let parent_cluster_no = if self.is_root()? { 0 } else { self.first_cluster_no as u32 };
+            if let Some(dot_dot) = dir.clone().into_data_entries().try_find("..")? {
+                dot_dot.entry().clone().write_first_cluster_no(parent_cluster_no)?;
+            }
+        }
+
+        Ok(data)
+    }
+
+
+    fn is_inside_of(&self, cluster_no: usize) -> FatResult<bool> {
+        let mut dir = self.clone();
+        loop {
+            if dir.first_cluster_no == cluster_no {
+                return Ok(true);
+            }
+            if dir.is_root()? {
+                return Ok(false);
+            }
+
+            dir = dir
+                .into_data_entries()
+                .try_find("..")?
+                .ok_or(FatError::NotfoundFile("..".to_string()))?
+                .into_dir()?;
+        }
+    }
+
+
+    pub(crate) fn create_entry(&mut self, name: &str, attribute: Attribute, first_cluster_no: u32) -> FatResult<ShortDirEntry<D>> {
+        if !is_valid_long_name(name) {
+            return Err(FatError::InvalidFileName(name.to_string()));
+        }
+
+        let name = trim_long_name(name);
+        let now = self.options.now();
+        self.insert_entry(name, new_short_entry_buff(&[b' '; 11], attribute.bits(), 0, first_cluster_no, now), None)
+    }
+
+
+    /// `name` must already be validated and trimmed.
+    fn insert_entry(&mut self, name: &str, mut entry_buff: [u8; 0x20], replacing: Option<usize>) -> FatResult<ShortDirEntry<D>> {
+        let mut entries = self.clone().into_data_entries();
+        let exists = entries.any(|data| data.is_named(name) && Some(data.entry().base.offset) != replacing);
+        if let Some(e) = entries.take_error() {
+            return Err(e);
+        }
+        if exists {
+            return Err(FatError::AlreadyExists(name.to_string()));
+        }
+
+        let (name_buff, nt_reserved, long_name) = match fit_short_name(name) {
+            Some((name_buff, nt_reserved)) => (name_buff, nt_reserved, None),
+            None => (self.generate_alias(name)?, 0, Some(name))
+        };
+        let long_entries = long_name
+            .map(|long_name| long_entries_buff(long_name, short_name_checksum(&name_buff)))
+            .unwrap_or_default();
+
+        let slots = self.find_free_slots(long_entries.len() + 1)?;
+        let mut bpb = self.entry.base.bpb.clone();
+        for (buff, offset) in long_entries.iter().zip(&slots) {
+            bpb.write(buff, *offset)?;
+        }
+
+        let offset = slots[long_entries.len()];
+        entry_buff[..11].copy_from_slice(&name_buff);
+        entry_buff[12] = nt_reserved;
+        bpb.write(&entry_buff, offset)?;
+
+        let long_name = long_name.map(|long_name| LongName::new(long_name.to_string(), slots[..long_entries.len()].to_vec()));
+        Ok(ShortDirEntry::new(BaseDirEntry::new(bpb, offset))
+            .with_long_name(long_name))
+    }
+
+
+    fn generate_alias(&self, name: &str) -> FatResult<[u8; 11]> {
+        let basis = basis_name(name);
+        let mut entries = self.clone().into_data_entries();
+        let short_names = entries
+            .by_ref()
+            .map(|data| data.name_buff())
+            .collect::<FatResult<Vec<_>>>()?;
+        if let Some(e) = entries.take_error() {
+            return Err(e);
+        }
+
+        (1..1_000_000)
+            .map(|n| with_numeric_tail(&basis, n))
+            .find(|alias| !short_names.contains(alias))
+            .ok_or(FatError::AlreadyExists(name.to_string()))
+    }
+
+
+    fn find_free_slots(&self, count: usize) -> FatResult<Vec<usize>> {
+        let mut slots = Vec::with_capacity(count);
+        let mut entries = self.clone();
+
+        while let Some(entry) = entries.next_entry()? {
+            match entry.status() {
+                Some(EntryStatus::End | EntryStatus::Deleted) => slots.push(entry.offset),
+                _ => slots.clear()
+            }
+
+            if slots.len() == count {
+                return Ok(slots);
+            }
+        }
+
+        if self.is_fixed_root() {
+            return Err(FatError::RootDirFull);
+        }
+
+        let mut bpb = self.entry.base.bpb.clone();
+        let mut table = FatTable::new(bpb.clone());
+        let cluster_bytes = bpb.bytes_per_cluster()?;
+        let mut last_cluster_no = table
+            .chain(self.first_cluster_no)
+            .last()
+            .transpose()?;
+
+        while slots.len() < count {
+            let cluster_no = table.append_cluster(last_cluster_no)?;
+            let offset = bpb.data_cluster_offset_at(cluster_no)?;
+            bpb.write(&vec![0; cluster_bytes], offset)?;
+
+            slots.extend((0..cluster_bytes).step_by(0x20).map(|i| offset + i).take(count - slots.len()));
+            last_cluster_no = Some(cluster_no);
+        }
+
+        Ok(slots)
+    }
+
+
+    #[inline]
+    fn is_fixed_root(&self) -> bool {
+        self.first_cluster_no == 0
+    }
+
+
+    fn next_fixed_root_entry(&mut self) -> FatResult<Option<BaseDirEntry<D>>> {
+        let bpb = &self.entry.base.bpb;
+        let region = match bpb.root_dir_region()? {
+            Some(region) => region,
+            None => return Ok(None)
+        };
+
+        let offset = region.start + self.index * 0x20;
+        if region.end <= offset {
+            return Ok(None);
+        }
+
+        self.index += 1;
+        Ok(Some(BaseDirEntry::new(bpb.clone(), offset)))
+    }
+
+
+    fn next_entry(&mut self) -> FatResult<Option<BaseDirEntry<D>>> {
+        if self.is_fixed_root() {
+            return self.next_fixed_root_entry();
+        }
+
+        let bpb = &self.entry.base.bpb;
+        let cluster_offset = match self.cluster_offset {
+            Some(cluster_offset) => cluster_offset,
+            None => match self.chain.next() {
+                Some(cluster_no) => bpb.data_cluster_offset_at(cluster_no?)?,
+                None => return Ok(None)
+            }
+        };
+
+        let entry = BaseDirEntry::new(bpb.clone(), cluster_offset + self.index * 0x20);
+        self.index += 1;
+        if bpb.bytes_per_cluster()? <= self.index * 0x20 {
+            self.cluster_offset = None;
+            self.index = 0;
         } else {
-            self.offset = offset + 0x20;
-            Some(entry)
+            self.cluster_offset = Some(cluster_offset);
+        }
+
+        Ok(Some(entry))
+    }
+
+
+    fn find_next(&mut self) -> Option<FatResult<BaseDirEntry<D>>> {
+        while !self.end {
+            let entry = match self.next_entry() {
+                Ok(entry) => entry?,
+                Err(e) => {
+                    self.end = true;
+                    return Some(Err(e));
+                }
+            };
+
+            match entry.status() {
+                Some(EntryStatus::End) => self.end = true,
+                Some(EntryStatus::Deleted) => continue,
+                Some(EntryStatus::JapaneseName) | None => return Some(Ok(entry))
+            }
         }
+
+        None
+    }
+}
+
+
+impl<D> ShortDirEntryWritable for DirEntries<D>
+    where D: FatDeviceAccessible + Clone + BpbReadable
+{
+    fn write_first_cluster_no(&mut self, cluster_no: u32) -> FatResult {
+        self.own_entry_mut()?.write_first_cluster_no(cluster_no)
+    }
+
+
+    fn write_file_size(&mut self, file_size: u32) -> FatResult {
+        self.own_entry_mut()?.write_file_size(file_size)
+    }
+
+
+    fn write_created(&mut self, created: FatDateTime) -> FatResult {
+        self.own_entry_mut()?.write_created(created)
+    }
+
+
+    fn write_modified(&mut self, modified: FatDateTime) -> FatResult {
+        self.own_entry_mut()?.write_modified(modified)
+    }
+
+
+    fn write_last_access_date(&mut self, date: FatDate) -> FatResult {
+        self.own_entry_mut()?.write_last_access_date(date)
+    }
+
+
+    fn write_attribute(&mut self, attribute: Attribute) -> FatResult {
+        self.own_entry_mut()?.write_attribute(attribute)
+    }
+
+
+    fn set_attribute(&mut self, flags: Attribute, enabled: bool) -> FatResult {
+        self.own_entry_mut()?.set_attribute(flags, enabled)
+    }
+
+
+    fn mark_deleted(&mut self) -> FatResult {
+        self.own_entry_mut()?.mark_deleted()
     }
 }
 
@@ -76,11 +477,7 @@ impl<D> Clone for DirEntries<D>
  where D: FatDeviceAccessible + Clone + BpbReadable
 {
     fn clone(&self) -> Self {
-        Self{
-            entry: self.entry.clone(),
-            base_offset: self.base_offset,
-            offset: 0,
-        }
+        Self::from_entry(self.entry.clone(), self.first_cluster_no, self.options.clone())
     }
 }
 
@@ -89,10 +486,10 @@ impl<D> Clone for DirEntries<D>
 impl<D> Iterator for DirEntries<D>
     where D: FatDeviceAccessible + Clone + BpbReadable
 {
-    type Item = BaseDirEntry<D>;
+    type Item = FatResult<BaseDirEntry<D>>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.find_next(self.offset(0))
+        self.find_next()
     }
 }
 
@@ -102,7 +499,513 @@ impl<D> Debug for DirEntries<D> where D: FatDeviceAccessible + Clone + BpbReadab
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f
             .debug_struct("DirEntries")
-            .field("base_offset", &format!("0x{:X}", self.base_offset))
+            .field("first_cluster_no", &format!("0x{:X}", self.first_cluster_no))
             .finish()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    use crate::Fat;
+    use crate::bpb::Bpb;
+    use crate::bpb::fs_info::FsInfo;
+    use crate::dir::data::dir::DirEntries;
+    use crate::dir::entry::Attribute;
+    use crate::dir::entry::base::DirEntryReadable;
+    use crate::dir::entry::short::{ShortDirEntryReadable, ShortDirEntryWritable};
+    use crate::error::FatError;
+    use crate::FatDeviceAccessible;
+    use crate::options::MountOptions;
+    use crate::table::FatTable;
+    use crate::test::{fat12_memory_device, fat16_memory_device, file_device, FixedTimeProvider, memory_device, MemoryDevice, read_fat32_entry, write_fat32_entry};
+    use crate::time::{FatDateTime, TimeProvider};
+
+    const ROOT_OFFSET: usize = 0x102000;
+
+
+    fn fill_root_with_deleted_entries(device: &mut MemoryDevice) {
+        for index in 7..32 {
+            device.write(&[0xE5], ROOT_OFFSET + index * 0x20).unwrap();
+        }
+    }
+
+
+    #[test]
+    fn it_sub_dir_entries() {
+        let names = Bpb::new(file_device())
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("TEST")
+            .unwrap()
+            .into_dir()
+            .unwrap()
+            .into_data_entries()
+            .map(|data| data.name().unwrap().into_string().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, [".", ".."]);
+    }
+
+
+    #[test]
+    fn it_root_dir_spans_clusters() {
+        let mut device = memory_device();
+        fill_root_with_deleted_entries(&mut device);
+
+        let mut entry = [0; 0x20];
+        device.read(&mut entry, ROOT_OFFSET + 0x40, 0x20).unwrap();
+        entry[..11].copy_from_slice(b"WORLD   TXT");
+        device.write(&entry, ROOT_OFFSET + (0x10 - 2) * 0x400).unwrap();
+        write_fat32_entry(&device, 2, 0x10);
+        write_fat32_entry(&device, 0x10, 0x0FFF_FFFF);
+
+        let world_txt = Bpb::new(device)
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("WORLD.TXT")
+            .unwrap()
+            .into_regular_file()
+            .unwrap()
+            .read_boxed()
+            .unwrap();
+
+        assert_eq!(world_txt, b"hello\n");
+    }
+
+
+    #[test]
+    fn it_root_dir_stops_at_end_of_chain() {
+        let mut device = memory_device();
+        fill_root_with_deleted_entries(&mut device);
+
+        let root = Bpb::new(device)
+            .unwrap()
+            .root_dir()
+            .unwrap();
+
+        assert_eq!(root.count(), 3);
+    }
+
+
+    #[test]
+    fn it_escaped_first_byte_entry_is_live() {
+        let mut device = memory_device();
+        device.write(&[0x05], ROOT_OFFSET + 0x40).unwrap();
+
+        let names = Bpb::new(device)
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .map(|data| data.name().unwrap().into_bytes())
+            .collect::<Vec<_>>();
+
+        assert!(names.contains(&b"\xE5ELLO.TXT".to_vec()));
+    }
+
+
+    #[test]
+    fn it_failed_open_on_broken_root_chain() {
+        let mut device = memory_device();
+        fill_root_with_deleted_entries(&mut device);
+        write_fat32_entry(&device, 2, 0x00FF_FFFF);
+
+        let result = Bpb::new(device)
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .open("WORLD.TXT");
+
+        assert!(matches!(result, Err(FatError::InvalidFatEntry(0x00FF_FFFF))));
+    }
+
+
+    #[test]
+    fn it_create_long_name_beside_similar_alias() {
+        let mut root = DirEntries::root(Bpb::new(memory_device()).unwrap(), MountOptions::new()).unwrap();
+        root.create_file("A_B.TXT").unwrap();
+        let file = root.create_file("a+b.txt").unwrap();
+
+        assert_eq!(file.name().unwrap().to_str(), Ok("A_B~1.TXT"));
+        assert_eq!(file.long_name(), Some("a+b.txt"));
+    }
+
+
+    #[test]
+    fn it_keep_target_if_overwriting_rename_fails() {
+        let fat = Fat::mount(fat16_memory_device()).unwrap();
+        fat.create_file("/FILE.TXT").unwrap().append(b"hello").unwrap();
+        fat.create_file("/TARGET.TXT").unwrap().append(b"world").unwrap();
+        for i in 0..510 {
+            fat.create_file(&format!("/F{i}")).unwrap();
+        }
+
+        assert_eq!(fat.rename_overwrite("/FILE.TXT", "/TARGET.TXT").err(), Some(FatError::RootDirFull));
+        assert_eq!(fat.open_file("/TARGET.TXT").unwrap().read_boxed().unwrap(), b"world");
+        assert_eq!(fat.open_file("/FILE.TXT").unwrap().read_boxed().unwrap(), b"hello");
+    }
+
+
+    #[test]
+    fn it_failed_set_attribute_on_root_dir() {
+        let fat = Fat::mount(memory_device()).unwrap();
+
+        assert_eq!(fat.open("/").unwrap().set_hidden(true), Err(FatError::RootDirHasNoEntry));
+        assert_eq!(fat.open("/MIKAN OS").unwrap().attribute(), Ok(Attribute::VOLUME_LABEL));
+        assert!(fat.open("/TEST").unwrap().set_hidden(true).is_ok());
+    }
+
+
+    #[test]
+    fn it_remove_dir_with_orphan_long_name_entry() {
+        let mut device = memory_device();
+        let fat = Fat::mount(device.clone()).unwrap();
+        let cluster_no = fat.create_dir("/NEWDIR").unwrap().first_cluster_no().unwrap() as usize;
+
+        let mut long_entry = [0; 0x20];
+        long_entry[0] = 0x41;
+        long_entry[1] = b'a';
+        long_entry[11] = 0x0F;
+        device.write(&long_entry, ROOT_OFFSET + (cluster_no - 2) * 0x400 + 0x40).unwrap();
+
+        fat.remove_dir("/NEWDIR").unwrap();
+        assert!(fat.open("/NEWDIR").is_err());
+    }
+
+
+    #[test]
+    fn it_create_file() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.create_file("/NEW.TXT")
+            .unwrap()
+            .append(b"abc")
+            .unwrap();
+
+        let mut new_txt = fat.open_file("/NEW.TXT").unwrap();
+        assert_eq!(new_txt.read_boxed().unwrap(), b"abc");
+        assert_eq!(new_txt.attribute(), Ok(Attribute::ARCHIVE));
+        assert_eq!(new_txt.long_name(), None);
+    }
+
+
+    #[test]
+    fn it_create_file_in_sub_dir() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.create_file("/TEST/NEW.TXT").unwrap();
+
+        assert!(fat.open_file("/TEST/NEW.TXT").is_ok());
+        assert!(fat.open_file("/NEW.TXT").is_err());
+    }
+
+
+    #[test]
+    fn it_create_lowercase_file_without_long_name() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.create_file("/readme.txt").unwrap();
+
+        let readme_txt = fat.open_file("/README.TXT").unwrap();
+        assert_eq!(readme_txt.name().unwrap().to_str(), Ok("readme.txt"));
+        assert_eq!(readme_txt.long_name(), None);
+    }
+
+
+    #[test]
+    fn it_create_long_name_file() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.create_file("/a long file name.txt").unwrap();
+        fat.create_file("/a long file name2.txt").unwrap();
+
+        let first = fat.open_file("/ALONGF~1.TXT").unwrap();
+        assert_eq!(first.long_name(), Some("a long file name.txt"));
+
+        let second = fat.open_file("/a long file name2.txt").unwrap();
+        assert_eq!(second.name().unwrap().to_str(), Ok("ALONGF~2.TXT"));
+    }
+
+
+    #[test]
+    fn it_failed_create_if_already_exists() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        assert_eq!(fat.create_file("/hello.TXT").err(), Some(FatError::AlreadyExists("hello.TXT".to_string())));
+        assert_eq!(fat.create_file("/test").err(), Some(FatError::AlreadyExists("test".to_string())));
+    }
+
+
+    #[test]
+    fn it_failed_create_if_invalid_name() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        assert_eq!(fat.create_file("/a:b").err(), Some(FatError::InvalidFileName("a:b".to_string())));
+        assert_eq!(fat.create_file("/...").err(), Some(FatError::InvalidFileName("...".to_string())));
+    }
+
+
+    #[test]
+    fn it_strip_trailing_dots_and_spaces() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        let world = fat.create_file("/WORLD.TXT. .").unwrap();
+
+        assert_eq!(world.name().unwrap().to_str(), Ok("WORLD.TXT"));
+        assert_eq!(world.long_name(), None);
+        assert_eq!(fat.create_dir("/hello.txt.").err(), Some(FatError::AlreadyExists("hello.txt".to_string())));
+    }
+
+
+    #[test]
+    fn it_create_files_beyond_one_dir_cluster() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        for i in 0..40 {
+            fat.create_file(&format!("/FILE{i}.TXT")).unwrap();
+        }
+
+        let root_clusters = FatTable::new(fat.bpb.clone())
+            .chain(2)
+            .count();
+        assert_eq!(root_clusters, 2);
+        assert!((0..40).all(|i| fat.open_file(&format!("/FILE{i}.TXT")).is_ok()));
+        assert!(fat.open_file("/HELLO.TXT").is_ok());
+    }
+
+
+    #[test]
+    fn it_create_after_remove_file() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.remove_file("/HELLO.TXT").unwrap();
+        fat.create_file("/hello.txt")
+            .unwrap()
+            .append(b"bye\n")
+            .unwrap();
+
+        assert_eq!(fat.open_file("/HELLO.TXT").unwrap().read_boxed().unwrap(), b"bye\n");
+    }
+
+
+    #[test]
+    fn it_create_dir() {
+        let device = memory_device();
+        let fat = Fat::mount(device.clone()).unwrap();
+        let dir = fat.create_dir("/NEWDIR").unwrap();
+        let cluster_no = dir.first_cluster_no().unwrap();
+
+        let entries = fat
+            .open("/newdir")
+            .unwrap()
+            .into_dir()
+            .unwrap()
+            .into_data_entries()
+            .map(|data| (data.name().unwrap().into_string().unwrap(), data.first_cluster_no().unwrap()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(entries, [(".".to_string(), cluster_no), ("..".to_string(), 0)]);
+        assert_eq!(read_fat32_entry(&device, 0, cluster_no as usize), 0x0FFF_FFFF);
+    }
+
+
+    #[test]
+    fn it_create_nested_dir() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        let parent = fat.create_dir("/a long dir name").unwrap();
+        fat.create_dir("/a long dir name/SUB").unwrap();
+        fat.create_file("/a long dir name/SUB/A.TXT").unwrap();
+
+        let dot_dot = fat.open("/a long dir name/SUB/..").unwrap();
+        assert_eq!(dot_dot.first_cluster_no(), parent.first_cluster_no());
+        assert!(fat.open_file("/A LONG DIR NAME/sub/../SUB/a.txt").is_ok());
+        assert!(fat.open_file("/a long dir name/SUB/../../HELLO.TXT").is_ok());
+    }
+
+
+    #[test]
+    fn it_remove_dir() {
+        let device = memory_device();
+        let fat = Fat::mount(device.clone()).unwrap();
+        let cluster_no = fat.create_dir("/NEWDIR").unwrap().first_cluster_no().unwrap();
+        fat.create_file("/NEWDIR/A.TXT").unwrap();
+
+        assert_eq!(fat.remove_dir("/NEWDIR"), Err(FatError::DirectoryNotEmpty("NEWDIR".to_string())));
+
+        fat.remove_file("/NEWDIR/A.TXT").unwrap();
+        fat.remove_dir("/NEWDIR").unwrap();
+        assert!(fat.open("/NEWDIR").is_err());
+        assert_eq!(read_fat32_entry(&device, 0, cluster_no as usize), 0);
+    }
+
+
+    #[test]
+    fn it_failed_create_dir_if_already_exists() {
+        let device = memory_device();
+        let fat = Fat::mount(device.clone()).unwrap();
+        assert_eq!(fat.create_dir("/TEST").err().unwrap(), FatError::AlreadyExists("TEST".to_string()));
+        assert_eq!(FsInfo::new(device, 0x200).free_count(), Ok(130037));
+    }
+
+
+    #[test]
+    fn it_rename_in_same_dir() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.rename("/HELLO.TXT", "/a long name.txt").unwrap();
+
+        assert!(fat.open("/HELLO.TXT").is_err());
+        let mut file = fat.open_file("/a long name.txt").unwrap();
+        assert_eq!(file.long_name(), Some("a long name.txt"));
+        assert_eq!(file.read_boxed().unwrap(), b"hello\n");
+    }
+
+
+    #[test]
+    fn it_rename_changes_case_only() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.rename("/hello.txt", "/Hello.txt").unwrap();
+
+        assert_eq!(fat.open_file("/HELLO.TXT").unwrap().long_name(), Some("Hello.txt"));
+    }
+
+
+    #[test]
+    fn it_keep_entry_if_case_only_rename_fails() {
+        let fat = Fat::mount(fat16_memory_device()).unwrap();
+        fat.create_file("/FILE.TXT").unwrap().append(b"hello").unwrap();
+        for i in 0..511 {
+            fat.create_file(&format!("/F{i}")).unwrap();
+        }
+
+        assert_eq!(fat.rename("/FILE.TXT", "/File.txt").err().unwrap(), FatError::RootDirFull);
+        assert_eq!(fat.open_file("/FILE.TXT").unwrap().read_boxed().unwrap(), b"hello");
+    }
+
+
+    #[test]
+    fn it_move_file_into_dir() {
+        let device = memory_device();
+        let fat = Fat::mount(device.clone()).unwrap();
+        fat.rename("/HELLO.TXT", "/TEST/WORLD.TXT").unwrap();
+
+        assert!(fat.open("/HELLO.TXT").is_err());
+        let mut file = fat.open_file("/TEST/WORLD.TXT").unwrap();
+        assert_eq!(file.first_cluster_no(), Ok(3));
+        assert_eq!(file.read_boxed().unwrap(), b"hello\n");
+        assert_eq!(FsInfo::new(device, 0x200).free_count(), Ok(130037));
+    }
+
+
+    #[test]
+    fn it_move_dir_fixes_dot_dot() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.create_dir("/SUB").unwrap();
+        fat.create_file("/SUB/A.TXT").unwrap();
+        fat.rename("/SUB", "/TEST/SUB").unwrap();
+
+        let test = fat.open("/TEST").unwrap();
+        let dot_dot = fat.open("/TEST/SUB/..").unwrap();
+        assert_eq!(dot_dot.first_cluster_no(), test.first_cluster_no());
+        assert!(fat.open_file("/TEST/SUB/../SUB/A.TXT").is_ok());
+
+        fat.rename("/TEST/SUB", "/SUB").unwrap();
+        assert_eq!(fat.open("/SUB/..").unwrap().first_cluster_no(), Ok(0));
+    }
+
+
+    #[test]
+    fn it_failed_move_dir_into_itself() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.create_dir("/TEST/SUB").unwrap();
+
+        assert_eq!(fat.rename("/TEST", "/TEST/OTHER").err().unwrap(), FatError::MoveIntoItself("TEST".to_string()));
+        assert_eq!(fat.rename("/TEST", "/TEST/SUB/OTHER").err().unwrap(), FatError::MoveIntoItself("TEST".to_string()));
+        assert!(fat.open("/TEST/SUB").is_ok());
+    }
+
+
+    #[test]
+    fn it_rename_overwrite_only_if_asked() {
+        let device = memory_device();
+        let fat = Fat::mount(device.clone()).unwrap();
+        fat.create_file("/TEST/WORLD.TXT").unwrap().append(b"world").unwrap();
+
+        assert_eq!(fat.rename("/HELLO.TXT", "/TEST/WORLD.TXT").err().unwrap(), FatError::AlreadyExists("WORLD.TXT".to_string()));
+        assert!(fat.open("/HELLO.TXT").is_ok());
+
+        fat.rename_overwrite("/HELLO.TXT", "/TEST/WORLD.TXT").unwrap();
+        assert!(fat.open("/HELLO.TXT").is_err());
+        assert_eq!(fat.open_file("/TEST/WORLD.TXT").unwrap().read_boxed().unwrap(), b"hello\n");
+        assert_eq!(FsInfo::new(device, 0x200).free_count(), Ok(130037));
+    }
+
+
+    #[test]
+    fn it_failed_rename_volume_label() {
+        let fat = Fat::mount(memory_device()).unwrap();
+
+        assert_eq!(fat.rename("/MIKAN OS", "/LABEL").err(), Some(FatError::IsVolumeLabel("MIKAN OS".to_string())));
+        assert!(fat.open("/MIKAN OS").is_ok());
+    }
+
+
+    #[test]
+    fn it_failed_overwrite_dir_with_file() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        assert_eq!(fat.rename_overwrite("/HELLO.TXT", "/TEST").err().unwrap(), FatError::IsDirectory("TEST".to_string()));
+    }
+
+
+    #[test]
+    fn it_write_file_on_fat12() {
+        let fat = Fat::mount(fat12_memory_device()).unwrap();
+        let content = (0..0x500).map(|i| i as u8).collect::<Vec<_>>();
+        fat.create_file("/a long name.bin").unwrap().append(&content).unwrap();
+
+        let mut file = fat.open_file("/A LONG NAME.BIN").unwrap();
+        assert_eq!(file.read_boxed().unwrap(), content);
+        assert_eq!(fat.root_dir().unwrap().count(), 1);
+    }
+
+
+    #[test]
+    fn it_failed_create_file_if_fat12_root_is_full() {
+        let fat = Fat::mount(fat12_memory_device()).unwrap();
+        for i in 0..224 {
+            fat.create_file(&format!("/F{i}.TXT")).unwrap();
+        }
+
+        assert_eq!(fat.create_file("/FULL.TXT").err().unwrap(), FatError::RootDirFull);
+        assert_eq!(fat.root_dir().unwrap().count(), 224);
+    }
+
+
+    #[test]
+    fn it_create_dir_on_fat16() {
+        let fat = Fat::mount(fat16_memory_device()).unwrap();
+        fat.create_dir("/DIR").unwrap();
+        fat.create_dir("/DIR/SUB").unwrap();
+        fat.create_file("/DIR/SUB/A.TXT").unwrap().append(b"hello").unwrap();
+
+        assert_eq!(fat.open("/DIR/..").unwrap().first_cluster_no(), Ok(0));
+        assert_eq!(fat.open_file("/DIR/SUB/../SUB/A.TXT").unwrap().read_boxed().unwrap(), b"hello");
+        assert_eq!(fat.rename("/DIR", "/DIR/SUB/DIR").err().unwrap(), FatError::MoveIntoItself("DIR".to_string()));
+
+        fat.rename("/DIR/SUB", "/SUB").unwrap();
+        assert_eq!(fat.open("/SUB/..").unwrap().first_cluster_no(), Ok(0));
+        assert!(fat.open_file("/SUB/../SUB/A.TXT").is_ok());
+    }
+
+
+    #[test]
+    fn it_stamp_created_file() {
+        let fat = Fat::mount_with(memory_device(), MountOptions::new().with_time_provider(FixedTimeProvider)).unwrap();
+        fat.create_file("/WORLD.TXT").unwrap();
+        fat.create_dir("/DIR").unwrap();
+
+        let world = fat.open("/WORLD.TXT").unwrap();
+        assert_eq!(world.created(), Ok(FixedTimeProvider.now()));
+        assert_eq!(world.modified(), Ok(FixedTimeProvider.now()));
+        assert_eq!(world.last_access_date(), Ok(FixedTimeProvider.now().date()));
+        assert_eq!(fat.open("/DIR/.").unwrap().created(), Ok(FixedTimeProvider.now()));
+
+        let epoch = Fat::mount(memory_device()).unwrap();
+        assert_eq!(epoch.create_file("/WORLD.TXT").unwrap().created(), Ok(FatDateTime::EPOCH));
+    }
+}
@@ -4,16 +4,21 @@ use alloc::vec::Vec;
 use auto_delegate::Delegate;
 
 use crate::bpb::BpbReadable;
-use crate::dir::entry::short::{ShortDirEntry, ShortDirEntryReadable};
+use crate::dir::entry::base::DirEntryReadable;
+use crate::dir::entry::short::{ShortDirEntry, ShortDirEntryReadable, ShortDirEntryWritable};
 use crate::error::{FatError, FatResult};
 use crate::FatDeviceAccessible;
+use crate::options::MountOptions;
+use crate::table::{FatEntry, FatTable};
 
 #[derive(Delegate)]
 pub struct RegularFile<D>
     where D: FatDeviceAccessible + Clone + BpbReadable
 {
-    #[to(ShortDirEntryReadable, DirEntryReadable)]
+    #[to(ShortDirEntryReadable, ShortDirEntryWritable, DirEntryReadable)]
     pub entry: ShortDirEntry<D>,
+
+    options: MountOptions,
 }
 
 
@@ -21,27 +26,200 @@ impl<D> RegularFile<D>
     where D: FatDeviceAccessible + Clone + BpbReadable
 {
     #[inline]
-    pub fn new(entry: ShortDirEntry<D>) -> RegularFile<D> {
+    pub fn new(entry: ShortDirEntry<D>, options: MountOptions) -> RegularFile<D> {
         Self {
-            entry
+            entry,
+            options,
         }
     }
 
 
-    pub fn read_buff(&self, buff: &mut [u8]) -> FatResult {
-        let offset = self.entry.base.bpb.data_cluster_offset_at(self.entry.first_cluster_no()? as usize)?;
+    #[inline]
+    pub fn long_name(&self) -> Option<&str> {
+        self.entry.long_name()
+    }
+
+
+    /// Also stamps the last access date when the mount options ask for it.
+    ///
+    /// That update is best effort, like `relatime` on other systems: the file's data has already been
+    /// read when it runs, and a volume on write-protected media must stay readable, so a failed write
+    /// of the access date is ignored rather than reported.
+    pub fn read_buff(&mut self, buff: &mut [u8]) -> FatResult {
         let file_size = self.entry.file_size_usize()?;
         if buff.len() < file_size {
             return Err(FatError::BufferToSmall(file_size, buff.len()));
         }
 
-        self.entry.base.bpb.read(buff, offset, self.entry.file_size()? as usize)?;
+        let bpb = &self.entry.base.bpb;
+        let cluster_bytes = bpb.bytes_per_cluster()?;
+        let mut read_bytes = 0;
+
+        for cluster_no in FatTable::new(bpb.clone()).chain(self.entry.first_cluster_no()? as usize) {
+            if file_size <= read_bytes {
+                break;
+            }
+
+            let offset = bpb.data_cluster_offset_at(cluster_no?)?;
+            let bytes = cluster_bytes.min(file_size - read_bytes);
+            bpb.read(&mut buff[read_bytes..(read_bytes + bytes)], offset, bytes)?;
+            read_bytes += bytes;
+        }
+
+        if read_bytes < file_size {
+            return Err(FatError::UnexpectedEndOfChain(read_bytes, file_size));
+        }
+
+        let _ = self.entry.touch_accessed(&self.options);
+        Ok(())
+    }
+
+
+    pub fn write_at(&mut self, offset: usize, buff: &[u8]) -> FatResult {
+        self.ensure_not_volume_label()?;
+        let file_size = self.entry.file_size_usize()?;
+        let end = offset + buff.len();
+        if file_size < end {
+            return Err(FatError::WriteBeyondFileSize(end, file_size));
+        }
+
+        let cluster_bytes = self.entry.base.bpb.bytes_per_cluster()?;
+        let chain = FatTable::new(self.entry.base.bpb.clone())
+            .chain(self.entry.first_cluster_no()? as usize)
+            .skip(offset / cluster_bytes);
+
+        let mut written = 0;
+        let mut cluster_pos = offset % cluster_bytes;
+        for cluster_no in chain {
+            if buff.len() <= written {
+                break;
+            }
+
+            let bytes = (cluster_bytes - cluster_pos).min(buff.len() - written);
+            let cluster_offset = self.entry.base.bpb.data_cluster_offset_at(cluster_no?)?;
+            self.entry.base.bpb.write(&buff[written..(written + bytes)], cluster_offset + cluster_pos)?;
+            written += bytes;
+            cluster_pos = 0;
+        }
+
+        if written < buff.len() {
+            return Err(FatError::UnexpectedEndOfChain(offset + written, end));
+        }
+
+        self.entry.touch_modified(&self.options)
+    }
+
+
+    pub fn append(&mut self, buff: &[u8]) -> FatResult {
+        let offset = self.entry.file_size_usize()?;
+        self.set_len(offset + buff.len())?;
+        self.write_at(offset, buff)
+    }
+
+
+    pub fn set_len(&mut self, len: usize) -> FatResult {
+        self.ensure_not_volume_label()?;
+        let file_size = u32::try_from(len).map_err(|_| FatError::FileTooLarge(len))?;
+        let old_len = self.entry.file_size_usize()?;
+        let cluster_bytes = self.entry.base.bpb.bytes_per_cluster()?;
+        let mut table = FatTable::new(self.entry.base.bpb.clone());
+        let mut clusters = table
+            .chain(self.entry.first_cluster_no()? as usize)
+            .collect::<FatResult<Vec<usize>>>()?;
+
+        let needs = len.div_ceil(cluster_bytes);
+        if clusters.len() < needs {
+            self.extend_clusters(&mut table, &mut clusters, needs)?;
+        } else if needs < clusters.len() {
+            if let Some(last) = needs.checked_sub(1) {
+                table.set_entry_at(clusters[last], FatEntry::EndOfChain)?;
+            }
+            table.free_clusters(&clusters[needs..])?;
+            clusters.truncate(needs);
+        }
+
+        self.entry.write_first_cluster_no(clusters.first().copied().unwrap_or(0) as u32)?;
+        self.entry.write_file_size(file_size)?;
+
+        if old_len < len {
+            self.fill_zero(&clusters, old_len, len)?;
+        }
+
+        self.entry.touch_modified(&self.options)
+    }
+
+
+    fn ensure_not_volume_label(&self) -> FatResult {
+        if self.entry.attribute()?.is_volume_label() {
+            return Err(FatError::IsVolumeLabel(self.entry.name()?.to_string_lossy().into_owned()));
+        }
+
         Ok(())
     }
 
 
+    fn extend_clusters(&mut self, table: &mut FatTable<D>, clusters: &mut Vec<usize>, needs: usize) -> FatResult {
+        let old_len = clusters.len();
+
+        while clusters.len() < needs {
+            match table.append_cluster(clusters.last().copied()) {
+                Ok(cluster_no) => clusters.push(cluster_no),
+                Err(e) => {
+                    table.free_clusters(&clusters[old_len..])?;
+                    clusters.truncate(old_len);
+                    if let Some(last) = clusters.last() {
+                        table.set_entry_at(*last, FatEntry::EndOfChain)?;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+
+    fn fill_zero(&mut self, clusters: &[usize], from: usize, to: usize) -> FatResult {
+        let cluster_bytes = self.entry.base.bpb.bytes_per_cluster()?;
+        let zeros = vec![0; cluster_bytes];
+
+        let mut pos = from;
+        while pos < to {
+            let cluster_pos = pos % cluster_bytes;
+            let bytes = (cluster_bytes - cluster_pos).min(to - pos);
+            let offset = self.entry.base.bpb.data_cluster_offset_at(clusters[pos / cluster_bytes])?;
+            self.entry.base.bpb.write(&zeros[..bytes], offset + cluster_pos)?;
+            pos += bytes;
+        }
+
+        Ok(())
+    }
+
+
+    pub fn remove(self) -> FatResult {
+        self.ensure_removable()?;
+        self.force_remove()
+    }
+
+
+    pub(crate) fn ensure_removable(&self) -> FatResult {
+        if self.entry.attribute()?.is_read_only() {
+            return Err(FatError::ReadOnly(self.entry.name()?.to_string_lossy().into_owned()));
+        }
+
+        self.ensure_not_volume_label()
+    }
+
+
+    pub fn force_remove(mut self) -> FatResult {
+        self.ensure_not_volume_label()?;
+        FatTable::new(self.entry.base.bpb.clone()).free_chain(self.entry.first_cluster_no()? as usize)?;
+        self.entry.mark_deleted()
+    }
+
+
     #[cfg(feature = "alloc")]
-    pub fn read_boxed(&self) -> FatResult<Vec<u8>> {
+    pub fn read_boxed(&mut self) -> FatResult<Vec<u8>> {
         let size = self.entry.file_size()? as usize;
         let mut buff = vec![0; size];
         self.read_buff(&mut buff)?;
@@ -53,12 +231,70 @@ impl<D> RegularFile<D>
 
 #[cfg(test)]
 mod tests {
-    use crate::bpb::BpbFat32;
-    use crate::test::file_device;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    use crate::Fat;
+    use crate::bpb::Bpb;
+    use crate::bpb::fs_info::FsInfo;
+    use crate::dir::data::file::RegularFile;
+    use crate::dir::entry::Attribute;
+    use crate::dir::entry::short::ShortDirEntryReadable;
+    use crate::error::{FatDeviceError, FatError};
+    use crate::FatDeviceAccessible;
+    use crate::options::MountOptions;
+    use crate::test::{file_device, FixedTimeProvider, memory_device, MemoryDevice, read_fat32_entry, write_fat32_entry};
+    use crate::time::{FatDate, TimeProvider};
+
+    #[derive(Clone, Debug)]
+    struct ReadOnlyDevice(MemoryDevice);
+
+    impl FatDeviceAccessible for ReadOnlyDevice {
+        fn read(&self, buff: &mut [u8], offset: usize, bytes: usize) -> Result<(), FatDeviceError> {
+            self.0.read(buff, offset, bytes)
+        }
+
+
+        fn write(&mut self, _buff: &[u8], _offset: usize) -> Result<(), FatDeviceError> {
+            Err(FatDeviceError::StatusCode(-1))
+        }
+    }
+
+
+    fn open_hello_txt_with<D>(device: D, options: MountOptions) -> RegularFile<Bpb<D>>
+        where D: FatDeviceAccessible + Clone
+    {
+        Fat::mount_with(device, options)
+            .unwrap()
+            .open_file("/HELLO.TXT")
+            .unwrap()
+    }
+
+
+    fn open_hello_txt(device: MemoryDevice) -> RegularFile<Bpb<MemoryDevice>> {
+        open_hello_txt_with(device, MountOptions::new())
+    }
+
+
+    fn write_hello_txt_size(device: &mut MemoryDevice, file_size: usize) {
+        device.write(&(file_size as u32).to_le_bytes(), 0x102040 + 28).unwrap();
+    }
+
+
+    fn exists_hello_txt(device: MemoryDevice) -> bool {
+        Bpb::new(device)
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("HELLO.TXT")
+            .is_some()
+    }
+
 
     #[test]
     fn it_hello_txt_file_name() {
-        let file = BpbFat32::new(file_device())
+        let mut file = Bpb::new(file_device())
+            .unwrap()
             .root_dir()
             .unwrap()
             .find("HELLO.TXT")
@@ -68,4 +304,225 @@ mod tests {
 
         assert_eq!(&file.read_boxed().unwrap(), &[0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x0A]);
     }
-}
\ No newline at end of file
+
+
+    #[test]
+    fn it_read_fragmented_file() {
+        let mut device = memory_device();
+        let cluster_bytes = 0x400;
+        let data_offset = |cluster_no: usize| 0x102000 + (cluster_no - 2) * cluster_bytes;
+        let expect = (0..(cluster_bytes * 2 + 10))
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<u8>>();
+
+        device.write(&expect[..cluster_bytes], data_offset(3)).unwrap();
+        device.write(&expect[cluster_bytes..(cluster_bytes * 2)], data_offset(0x10)).unwrap();
+        device.write(&expect[(cluster_bytes * 2)..], data_offset(0x08)).unwrap();
+        write_fat32_entry(&device, 3, 0x10);
+        write_fat32_entry(&device, 0x10, 0x08);
+        write_fat32_entry(&device, 0x08, 0x0FFF_FFFF);
+        write_hello_txt_size(&mut device, expect.len());
+
+        assert_eq!(open_hello_txt(device).read_boxed().unwrap(), expect);
+    }
+
+
+    #[test]
+    fn it_failed_read_if_chain_is_shorter_than_file_size() {
+        let mut device = memory_device();
+        write_hello_txt_size(&mut device, 0x800);
+
+        assert_eq!(open_hello_txt(device).read_boxed(), Err(FatError::UnexpectedEndOfChain(0x400, 0x800)));
+    }
+
+
+    #[test]
+    fn it_overwrite_hello_txt() {
+        let mut file = open_hello_txt(memory_device());
+        file.write_at(1, b"ELL").unwrap();
+
+        assert_eq!(file.read_boxed().unwrap(), b"hELLo\n");
+    }
+
+
+    #[test]
+    fn it_failed_write_beyond_file_size() {
+        let mut file = open_hello_txt(memory_device());
+
+        assert_eq!(file.write_at(4, b"o!\n"), Err(FatError::WriteBeyondFileSize(7, 6)));
+        assert_eq!(file.read_boxed().unwrap(), b"hello\n");
+    }
+
+
+    #[test]
+    fn it_overwrite_across_clusters() {
+        let mut device = memory_device();
+        write_fat32_entry(&device, 3, 0x10);
+        write_fat32_entry(&device, 0x10, 0x08);
+        write_fat32_entry(&device, 0x08, 0x0FFF_FFFF);
+        write_hello_txt_size(&mut device, 0x400 * 3);
+
+        let mut file = open_hello_txt(device);
+        let buff = [0xAB; 0x500];
+        file.write_at(0x3F0, &buff).unwrap();
+
+        let read = file.read_boxed().unwrap();
+        assert_eq!(&read[0x3F0..0x8F0], &buff);
+        assert_eq!(&read[..6], b"hello\n");
+        assert!(read[0x8F0..].iter().all(|b| *b != 0xAB));
+    }
+
+
+
+    #[test]
+    fn it_grow_file() {
+        let device = memory_device();
+        let mut file = open_hello_txt(device.clone());
+        file.set_len(0x900).unwrap();
+
+        let mut file = open_hello_txt(device.clone());
+        let buff = file.read_boxed().unwrap();
+        assert_eq!(buff.len(), 0x900);
+        assert_eq!(&buff[..6], b"hello\n");
+        assert!(buff[6..].iter().all(|b| *b == 0));
+
+        assert_eq!(read_fat32_entry(&device, 0, 3), 6);
+        assert_eq!(read_fat32_entry(&device, 0, 6), 7);
+        assert_eq!(read_fat32_entry(&device, 0, 7), 0x0FFF_FFFF);
+        assert_eq!(read_fat32_entry(&device, 1, 3), 6);
+        assert_eq!(read_fat32_entry(&device, 1, 6), 7);
+        assert_eq!(read_fat32_entry(&device, 1, 7), 0x0FFF_FFFF);
+    }
+
+
+    #[test]
+    fn it_shrink_file() {
+        let device = memory_device();
+        let mut file = open_hello_txt(device.clone());
+        file.set_len(0x900).unwrap();
+        file.set_len(3).unwrap();
+
+        assert_eq!(open_hello_txt(device.clone()).read_boxed().unwrap(), b"hel");
+        assert_eq!(read_fat32_entry(&device, 0, 3), 0x0FFF_FFFF);
+        assert_eq!(read_fat32_entry(&device, 0, 4), 0);
+        assert_eq!(read_fat32_entry(&device, 1, 6), 0);
+    }
+
+
+    #[test]
+    fn it_truncate_to_zero() {
+        let device = memory_device();
+        let mut file = open_hello_txt(device.clone());
+        file.set_len(0).unwrap();
+
+        let mut file = open_hello_txt(device.clone());
+        assert_eq!(file.first_cluster_no(), Ok(0));
+        assert_eq!(file.read_boxed().unwrap(), b"");
+        assert_eq!(read_fat32_entry(&device, 0, 3), 0);
+        assert_eq!(read_fat32_entry(&device, 1, 3), 0);
+    }
+
+
+    #[test]
+    fn it_append() {
+        let device = memory_device();
+        let mut file = open_hello_txt(device.clone());
+        file.append(b"world\n").unwrap();
+
+        assert_eq!(open_hello_txt(device).read_boxed().unwrap(), b"hello\nworld\n");
+    }
+
+
+    #[test]
+    fn it_append_to_empty_file() {
+        let device = memory_device();
+        let mut file = open_hello_txt(device.clone());
+        file.set_len(0).unwrap();
+
+        let data = [0x5A; 0x500];
+        file.append(&data).unwrap();
+
+        let mut file = open_hello_txt(device);
+        assert_eq!(file.first_cluster_no(), Ok(3));
+        assert_eq!(file.read_boxed().unwrap(), data);
+    }
+
+
+    #[test]
+    fn it_remove_file() {
+        let device = memory_device();
+        open_hello_txt(device.clone()).remove().unwrap();
+
+        assert!(!exists_hello_txt(device.clone()));
+        assert_eq!(read_fat32_entry(&device, 0, 3), 0);
+        assert_eq!(read_fat32_entry(&device, 1, 3), 0);
+        assert_eq!(FsInfo::new(device.clone(), 0x200).free_count(), Ok(130038));
+
+        let mut status = [0; 2];
+        device.read(&mut status[..1], 0x102020, 1).unwrap();
+        device.read(&mut status[1..], 0x102040, 1).unwrap();
+        assert_eq!(status, [0xE5, 0xE5]);
+    }
+
+
+    #[test]
+    fn it_failed_remove_read_only_file() {
+        let mut device = memory_device();
+        device.write(&[Attribute::READ_ONLY.bits()], 0x102040 + 11).unwrap();
+
+        assert_eq!(open_hello_txt(device.clone()).remove(), Err(FatError::ReadOnly("HELLO.TXT".to_string())));
+        open_hello_txt(device.clone()).force_remove().unwrap();
+        assert!(!exists_hello_txt(device));
+    }
+
+
+    #[test]
+    fn it_failed_modify_volume_label() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        let open_label = || fat.open_file("/MIKAN OS").unwrap();
+        let label = || FatError::IsVolumeLabel("MIKAN OS".to_string());
+
+        assert_eq!(open_label().remove(), Err(label()));
+        assert_eq!(open_label().force_remove(), Err(label()));
+        assert_eq!(open_label().set_len(1), Err(label()));
+        assert_eq!(open_label().write_at(0, b""), Err(label()));
+        assert!(fat.open("/MIKAN OS").is_ok());
+    }
+
+
+    #[test]
+    fn it_stamp_modified_and_accessed_on_write_and_read() {
+        let mut hello = open_hello_txt_with(memory_device(), MountOptions::new().with_time_provider(FixedTimeProvider));
+        let created = hello.created().unwrap();
+
+        hello.read_boxed().unwrap();
+        assert_eq!(hello.last_access_date(), Ok(FixedTimeProvider.now().date()));
+        assert_ne!(hello.modified(), Ok(FixedTimeProvider.now()));
+
+        hello.append(b"world").unwrap();
+        assert_eq!(hello.modified(), Ok(FixedTimeProvider.now()));
+        assert_eq!(hello.created(), Ok(created));
+    }
+
+
+    #[test]
+    fn it_keep_access_date_if_disabled() {
+        let options = MountOptions::new()
+            .with_time_provider(FixedTimeProvider)
+            .with_update_access_date(false);
+        let mut hello = open_hello_txt_with(memory_device(), options);
+
+        hello.read_boxed().unwrap();
+        hello.write_at(0, b"H").unwrap();
+        assert_eq!(hello.last_access_date(), Ok(FatDate::new(2023, 7, 13).unwrap()));
+        assert_eq!(hello.modified(), Ok(FixedTimeProvider.now()));
+    }
+
+
+    #[test]
+    fn it_read_even_if_access_date_cannot_be_written() {
+        let mut hello = open_hello_txt_with(ReadOnlyDevice(memory_device()), MountOptions::new().with_time_provider(FixedTimeProvider));
+
+        assert_eq!(&*hello.read_boxed().unwrap(), b"hello\n");
+    }
+}
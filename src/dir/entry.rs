@@ -1,26 +1,189 @@
+use core::fmt::{Debug, Formatter};
+use core::ops::{BitAnd, BitOr};
+
 use auto_delegate::Delegate;
 use num_enum::TryFromPrimitive;
 
 use crate::bpb::BpbReadable;
 use crate::dir::entry::base::DirEntryReadable;
+use crate::dir::entry::long::LongDirEntry;
 use crate::dir::entry::short::ShortDirEntry;
 use crate::error::{FatError, FatResult};
 use crate::FatDeviceAccessible;
 
 pub mod short;
 pub mod base;
+pub mod long;
 
 
-#[derive(Debug, Copy, Clone, TryFromPrimitive, Eq, PartialEq, )]
-#[repr(u8)]
-pub enum Attribute {
-    Readonly = 0x01,
-    Hidden = 0x02,
-    System = 0x04,
-    VolumeLabel = 0x08,
-    Dir = 0x10,
-    Archive = 0x20,
-    LongName = 0x0f,
+#[derive(Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Attribute(u8);
+
+
+impl Attribute {
+    pub const READ_ONLY: Attribute = Attribute(0x01);
+
+    pub const HIDDEN: Attribute = Attribute(0x02);
+
+    pub const SYSTEM: Attribute = Attribute(0x04);
+
+    pub const VOLUME_LABEL: Attribute = Attribute(0x08);
+
+    pub const DIR: Attribute = Attribute(0x10);
+
+    pub const ARCHIVE: Attribute = Attribute(0x20);
+
+    pub const LONG_NAME: Attribute = Attribute(0x0F);
+
+    pub const USER_FLAGS: Attribute = Attribute(0x27);
+
+    const LONG_NAME_MASK: u8 = 0x3F;
+
+    const NAMES: [(Attribute, &'static str); 6] = [
+        (Self::READ_ONLY, "READ_ONLY"),
+        (Self::HIDDEN, "HIDDEN"),
+        (Self::SYSTEM, "SYSTEM"),
+        (Self::VOLUME_LABEL, "VOLUME_LABEL"),
+        (Self::DIR, "DIR"),
+        (Self::ARCHIVE, "ARCHIVE"),
+    ];
+
+
+    #[inline]
+    pub const fn empty() -> Attribute {
+        Self(0)
+    }
+
+
+    #[inline]
+    pub const fn from_bits(bits: u8) -> Attribute {
+        Self(bits)
+    }
+
+
+    #[inline]
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+
+    #[inline]
+    pub const fn contains(&self, other: Attribute) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+
+    #[inline]
+    pub const fn union(self, other: Attribute) -> Attribute {
+        Self(self.0 | other.0)
+    }
+
+
+    #[inline]
+    pub const fn difference(self, other: Attribute) -> Attribute {
+        Self(self.0 & !other.0)
+    }
+
+
+    #[inline]
+    pub fn set(&mut self, other: Attribute, enabled: bool) {
+        *self = if enabled { self.union(other) } else { self.difference(other) };
+    }
+
+
+    #[inline]
+    pub const fn is_long_name(&self) -> bool {
+        self.0 & Self::LONG_NAME_MASK == Self::LONG_NAME.0
+    }
+
+
+    #[inline]
+    pub const fn is_dir(&self) -> bool {
+        !self.is_long_name() && self.contains(Self::DIR)
+    }
+
+
+    #[inline]
+    pub const fn is_volume_label(&self) -> bool {
+        !self.is_long_name() && self.contains(Self::VOLUME_LABEL)
+    }
+
+
+    #[inline]
+    pub const fn is_file(&self) -> bool {
+        !self.is_long_name() && self.0 & (Self::DIR.0 | Self::VOLUME_LABEL.0) == 0
+    }
+
+
+    #[inline]
+    pub const fn is_read_only(&self) -> bool {
+        self.contains(Self::READ_ONLY)
+    }
+
+
+    #[inline]
+    pub const fn is_hidden(&self) -> bool {
+        self.contains(Self::HIDDEN)
+    }
+
+
+    #[inline]
+    pub const fn is_system(&self) -> bool {
+        self.contains(Self::SYSTEM)
+    }
+
+
+    #[inline]
+    pub const fn is_archive(&self) -> bool {
+        self.contains(Self::ARCHIVE)
+    }
+}
+
+
+impl BitOr for Attribute {
+    type Output = Attribute;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+
+impl BitAnd for Attribute {
+    type Output = Attribute;
+
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+
+impl Debug for Attribute {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if self.is_long_name() {
+            return write!(f, "LONG_NAME");
+        }
+
+        let mut names = Self::NAMES
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name);
+
+        match names.next() {
+            Some(first) => {
+                write!(f, "{first}")?;
+                names.try_for_each(|name| write!(f, " | {name}"))?;
+            }
+            None => write!(f, "(empty)")?
+        }
+
+        match self.0 & 0xC0 {
+            0 => Ok(()),
+            reserved => write!(f, " | 0x{reserved:02X}")
+        }
+    }
 }
 
 
@@ -39,6 +202,7 @@ pub enum DirEntry<D>
     where D: FatDeviceAccessible + Clone + BpbReadable
 {
     Short(ShortDirEntry<D>),
+    Long(LongDirEntry<D>),
 }
 
 
@@ -51,6 +215,12 @@ impl<D> DirEntry<D>
     }
 
 
+    #[inline]
+    pub const fn long(long: LongDirEntry<D>) -> DirEntry<D> {
+        Self::Long(long)
+    }
+
+
     #[inline]
     pub fn into_short(self) -> FatResult<ShortDirEntry<D>> {
         if let Self::Short(short) = self {
@@ -59,6 +229,56 @@ impl<D> DirEntry<D>
             Err(FatError::InvalidDirEntryType)
         }
     }
+
+
+    #[inline]
+    pub fn into_long(self) -> FatResult<LongDirEntry<D>> {
+        if let Self::Long(long) = self {
+            Ok(long)
+        } else {
+            Err(FatError::InvalidDirEntryType)
+        }
+    }
 }
 
 
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use crate::dir::entry::Attribute;
+
+    #[test]
+    fn it_keep_every_attribute_bit() {
+        let attribute = Attribute::from_bits(0x23);
+
+        assert_eq!(attribute, Attribute::READ_ONLY | Attribute::HIDDEN | Attribute::ARCHIVE);
+        assert!(attribute.is_read_only() && attribute.is_hidden() && attribute.is_archive());
+        assert!(attribute.is_file());
+        assert!(!attribute.is_system());
+        assert_eq!(format!("{:?}", attribute), "READ_ONLY | HIDDEN | ARCHIVE");
+    }
+
+
+    #[test]
+    fn it_classify_with_masks() {
+        assert!((Attribute::DIR | Attribute::ARCHIVE).is_dir());
+        assert!((Attribute::DIR | Attribute::HIDDEN | Attribute::SYSTEM).is_dir());
+        assert!(Attribute::from_bits(0x0F).is_long_name());
+        assert!(Attribute::from_bits(0xCF).is_long_name());
+        assert!(!Attribute::from_bits(0x0F).is_volume_label());
+        assert!(Attribute::empty().is_file());
+        assert!(!Attribute::empty().is_long_name());
+    }
+
+
+    #[test]
+    fn it_set_attribute() {
+        let mut attribute = Attribute::ARCHIVE;
+        attribute.set(Attribute::HIDDEN | Attribute::SYSTEM, true);
+        assert_eq!(attribute.bits(), 0x26);
+
+        attribute.set(Attribute::ARCHIVE, false);
+        assert_eq!(attribute.bits(), 0x06);
+    }
+}
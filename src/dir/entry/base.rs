@@ -6,8 +6,9 @@ use num_enum::TryFromPrimitive;
 
 use crate::bpb::BpbReadable;
 use crate::dir::entry::{Attribute, DirEntry, EntryStatus};
+use crate::dir::entry::long::LongDirEntry;
 use crate::dir::entry::short::ShortDirEntry;
-use crate::error::{FatError, FatResult};
+use crate::error::FatResult;
 use crate::FatDeviceAccessible;
 
 #[delegate]
@@ -26,12 +27,7 @@ pub trait DirEntryReadable {
 
     #[inline]
     fn attribute(&self) -> FatResult<Attribute> {
-        if self.attribute_raw()? == 0x00 {
-            return Ok(Attribute::LongName);
-        }
-
-        let attribute = Attribute::try_from_primitive(self.attribute_raw()?)?;
-        Ok(attribute)
+        Ok(Attribute::from_bits(self.attribute_raw()?))
     }
 }
 
@@ -61,9 +57,10 @@ impl<D> BaseDirEntry<D>
 
     #[inline]
     pub fn into_detail(self) -> FatResult<DirEntry<D>> {
-        match self.attribute()? {
-            Attribute::LongName => Err(FatError::InvalidDirEntryType),
-            _ => Ok(DirEntry::Short(ShortDirEntry::new(self)))
+        if self.attribute()?.is_long_name() {
+            Ok(DirEntry::Long(LongDirEntry::new(self)))
+        } else {
+            Ok(DirEntry::Short(ShortDirEntry::new(self)))
         }
     }
 }
@@ -0,0 +1,407 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+
+use auto_delegate::{delegate, Delegate};
+
+use crate::bpb::BpbReadable;
+use crate::dir::entry::Attribute;
+use crate::dir::entry::base::BaseDirEntry;
+use crate::error::FatResult;
+use crate::FatDeviceAccessible;
+
+pub const LAST_LONG_ENTRY: u8 = 0x40;
+
+pub const LONG_NAME_CHARS: usize = 13;
+
+
+#[delegate]
+pub trait LongDirEntryReadable {
+    fn ordinal(&self) -> FatResult<u8>;
+
+
+    fn checksum(&self) -> FatResult<u8>;
+
+
+    fn name_fragment(&self) -> FatResult<[u16; LONG_NAME_CHARS]>;
+
+
+    #[inline]
+    fn order(&self) -> FatResult<u8> {
+        Ok(self.ordinal()? & !LAST_LONG_ENTRY)
+    }
+
+
+    #[inline]
+    fn is_last(&self) -> FatResult<bool> {
+        Ok(self.ordinal()? & LAST_LONG_ENTRY != 0)
+    }
+}
+
+
+#[derive(Clone, Delegate)]
+pub struct LongDirEntry<D>
+    where D: FatDeviceAccessible + BpbReadable
+{
+    #[to(DirEntryReadable, BpbReadable, FatDeviceAccessible)]
+    pub(crate) base: BaseDirEntry<D>,
+}
+
+
+impl<D> LongDirEntry<D>
+    where D: FatDeviceAccessible + Clone + BpbReadable
+{
+    #[inline]
+    pub const fn new(base: BaseDirEntry<D>) -> Self {
+        Self {
+            base
+        }
+    }
+}
+
+
+impl<D> LongDirEntryReadable for LongDirEntry<D>
+    where D: FatDeviceAccessible + Clone + BpbReadable
+{
+    #[inline]
+    fn ordinal(&self) -> FatResult<u8> {
+        self.base.bpb.read_u8(self.base.offset)
+    }
+
+
+    #[inline]
+    fn checksum(&self) -> FatResult<u8> {
+        self.base.bpb.read_u8(self.base.offset + 13)
+    }
+
+
+    fn name_fragment(&self) -> FatResult<[u16; LONG_NAME_CHARS]> {
+        let mut buff = [0; 0x20];
+        self.base.bpb.read(&mut buff, self.base.offset, 0x20)?;
+
+        let mut fragment = [0; LONG_NAME_CHARS];
+        let chars = buff[1..11]
+            .chunks(2)
+            .chain(buff[14..26].chunks(2))
+            .chain(buff[28..32].chunks(2));
+
+        for (c, bytes) in fragment.iter_mut().zip(chars) {
+            *c = u16::from_le_bytes([bytes[0], bytes[1]]);
+        }
+
+        Ok(fragment)
+    }
+}
+
+
+impl<D> Debug for LongDirEntry<D>
+    where D: FatDeviceAccessible + Clone + BpbReadable
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f
+            .debug_struct("LongDirEntry")
+            .field("ordinal", &self.ordinal())
+            .field("checksum", &self.checksum())
+            .finish()
+    }
+}
+
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct LongName {
+    name: String,
+
+    entry_offsets: Vec<usize>,
+}
+
+
+impl LongName {
+    #[inline]
+    pub const fn new(name: String, entry_offsets: Vec<usize>) -> LongName {
+        Self {
+            name,
+            entry_offsets,
+        }
+    }
+
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+
+    #[inline]
+    pub fn entry_offsets(&self) -> &[usize] {
+        &self.entry_offsets
+    }
+}
+
+
+#[derive(Debug, Default, Clone)]
+pub struct LongNameBuilder {
+    fragments: Vec<[u16; LONG_NAME_CHARS]>,
+
+    entry_offsets: Vec<usize>,
+
+    checksum: u8,
+
+    next_order: u8,
+}
+
+
+impl LongNameBuilder {
+    #[inline]
+    pub const fn new() -> LongNameBuilder {
+        Self {
+            fragments: Vec::new(),
+            entry_offsets: Vec::new(),
+            checksum: 0,
+            next_order: 0,
+        }
+    }
+
+
+    pub fn push<D>(&mut self, entry: &LongDirEntry<D>) -> FatResult
+        where D: FatDeviceAccessible + Clone + BpbReadable
+    {
+        let order = entry.order()?;
+        let checksum = entry.checksum()?;
+
+        if entry.is_last()? {
+            self.reset();
+            self.checksum = checksum;
+        } else if order != self.next_order || checksum != self.checksum {
+            self.reset();
+            return Ok(());
+        }
+
+        if order == 0 {
+            self.reset();
+            return Ok(());
+        }
+
+        self.fragments.push(entry.name_fragment()?);
+        self.entry_offsets.push(entry.base.offset);
+        self.next_order = order - 1;
+        Ok(())
+    }
+
+
+    pub fn build(&mut self, short_name_checksum: u8) -> Option<LongName> {
+        let complete = !self.fragments.is_empty()
+            && self.next_order == 0
+            && self.checksum == short_name_checksum;
+
+        let long_name = complete.then(|| {
+            let units = self
+                .fragments
+                .iter()
+                .rev()
+                .flatten()
+                .copied()
+                .take_while(|c| *c != 0x0000);
+
+            let name = char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect();
+
+            LongName::new(name, core::mem::take(&mut self.entry_offsets))
+        });
+
+        self.reset();
+        long_name
+    }
+
+
+    #[inline]
+    pub fn reset(&mut self) {
+        self.fragments.clear();
+        self.entry_offsets.clear();
+        self.next_order = 0;
+    }
+}
+
+
+pub fn long_entry_buff(ordinal: u8, checksum: u8, name: &[u16]) -> [u8; 0x20] {
+    let mut chars = name
+        .iter()
+        .copied()
+        .chain([0x0000])
+        .chain([0xFFFF; LONG_NAME_CHARS])
+        .take(LONG_NAME_CHARS)
+        .flat_map(u16::to_le_bytes);
+
+    let mut buff = [0; 0x20];
+    buff[0] = ordinal;
+    buff[11] = Attribute::LONG_NAME.bits();
+    buff[13] = checksum;
+    for i in (1..11).chain(14..26).chain(28..32) {
+        buff[i] = chars.next().unwrap_or(0xFF);
+    }
+
+    buff
+}
+
+
+pub fn long_entries_buff(long_name: &str, checksum: u8) -> Vec<[u8; 0x20]> {
+    let name = long_name.encode_utf16().collect::<Vec<u16>>();
+    let fragments = name.chunks(LONG_NAME_CHARS).collect::<Vec<_>>();
+
+    fragments
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, fragment)| {
+            let last = if i + 1 == fragments.len() { LAST_LONG_ENTRY } else { 0 };
+            long_entry_buff(last | (i + 1) as u8, checksum, fragment)
+        })
+        .collect()
+}
+
+
+#[inline]
+pub fn short_name_checksum(name_buff: &[u8; 11]) -> u8 {
+    name_buff
+        .iter()
+        .fold(0u8, |sum, b| sum.rotate_right(1).wrapping_add(*b))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+
+    use crate::bpb::Bpb;
+    use crate::dir::entry::base::BaseDirEntry;
+    use crate::dir::entry::long::{LAST_LONG_ENTRY, LONG_NAME_CHARS, long_entries_buff, long_entry_buff, LongDirEntry, LongDirEntryReadable, short_name_checksum};
+    use crate::FatDeviceAccessible;
+    use crate::test::{file_device, memory_device, MemoryDevice};
+
+    const ROOT_OFFSET: usize = 0x102000;
+
+
+    fn write_long_name_file(device: &mut MemoryDevice, long_name: &str, ordinals: [u8; 2], checksum: u8) {
+        let name = long_name.encode_utf16().collect::<Vec<u16>>();
+        let (head, tail) = name.split_at(LONG_NAME_CHARS);
+        device.write(&long_entry_buff(ordinals[0], checksum, tail), ROOT_OFFSET + 7 * 0x20).unwrap();
+        device.write(&long_entry_buff(ordinals[1], checksum, head), ROOT_OFFSET + 8 * 0x20).unwrap();
+
+        let mut short = [0; 0x20];
+        device.read(&mut short, ROOT_OFFSET + 0x40, 0x20).unwrap();
+        short[..11].copy_from_slice(b"ALONGF~1TXT");
+        device.write(&short, ROOT_OFFSET + 9 * 0x20).unwrap();
+    }
+
+
+    #[test]
+    fn it_hello_txt_long_entry() {
+        let entry = LongDirEntry::new(BaseDirEntry::new(Bpb::new(file_device()).unwrap(), ROOT_OFFSET + 0x20));
+
+        assert_eq!(entry.order(), Ok(1));
+        assert_eq!(entry.is_last(), Ok(true));
+        assert_eq!(entry.checksum(), Ok(short_name_checksum(b"HELLO   TXT")));
+        assert_eq!(
+            char::decode_utf16(entry.name_fragment().unwrap().into_iter().take(9))
+                .map(|c| c.unwrap())
+                .collect::<String>(),
+            "hello.txt"
+        );
+    }
+
+
+    #[test]
+    fn it_hello_txt_long_name() {
+        let hello_txt = Bpb::new(file_device())
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("hello.txt")
+            .unwrap();
+
+        assert_eq!(hello_txt.long_name(), Some("hello.txt"));
+    }
+
+
+    #[test]
+    fn it_test_dir_long_name() {
+        let test = Bpb::new(file_device())
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("TEST")
+            .unwrap();
+
+        assert_eq!(test.long_name(), Some("test"));
+    }
+
+
+    #[test]
+    fn it_long_name_spans_entries() {
+        let mut device = memory_device();
+        let checksum = short_name_checksum(b"ALONGF~1TXT");
+        write_long_name_file(&mut device, "a long file name.txt", [LAST_LONG_ENTRY | 2, 1], checksum);
+
+        let mut file = Bpb::new(device)
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("a long file name.txt")
+            .unwrap()
+            .into_regular_file()
+            .unwrap();
+
+        assert_eq!(file.long_name(), Some("a long file name.txt"));
+        assert_eq!(file.entry.long_entry_offsets(), &[ROOT_OFFSET + 7 * 0x20, ROOT_OFFSET + 8 * 0x20]);
+        assert_eq!(file.read_boxed().unwrap(), b"hello\n");
+    }
+
+
+    #[test]
+    fn it_ignore_long_name_if_checksum_mismatch() {
+        let mut device = memory_device();
+        let checksum = short_name_checksum(b"ALONGF~1TXT").wrapping_add(1);
+        write_long_name_file(&mut device, "a long file name.txt", [LAST_LONG_ENTRY | 2, 1], checksum);
+
+        let file = Bpb::new(device)
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("ALONGF~1.TXT")
+            .unwrap();
+
+        assert_eq!(file.long_name(), None);
+    }
+
+
+    #[test]
+    fn it_ignore_long_name_if_ordinals_are_out_of_order() {
+        let mut device = memory_device();
+        let checksum = short_name_checksum(b"ALONGF~1TXT");
+        write_long_name_file(&mut device, "a long file name.txt", [LAST_LONG_ENTRY | 3, 1], checksum);
+
+        let file = Bpb::new(device)
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("ALONGF~1.TXT")
+            .unwrap();
+
+        assert_eq!(file.long_name(), None);
+    }
+
+
+    #[test]
+    fn it_long_entries_buff() {
+        let checksum = short_name_checksum(b"ALONGF~1TXT");
+        let entries = long_entries_buff("a long file name.txt", checksum);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0][0], LAST_LONG_ENTRY | 2);
+        assert_eq!(entries[1][0], 1);
+        assert_eq!(&entries[0][1..7], &[b'a', 0, b'm', 0, b'e', 0]);
+        assert_eq!(&entries[0][28..32], &[0xFF; 4]);
+        assert!(entries.iter().all(|entry| entry[13] == checksum));
+    }
+}
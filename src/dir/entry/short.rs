@@ -8,16 +8,26 @@ use crate::bpb::BpbReadable;
 use crate::dir::data::Data;
 use crate::dir::data::dir::DirEntries;
 use crate::dir::data::file::RegularFile;
-use crate::dir::entry::Attribute;
+use crate::dir::entry::{Attribute, EntryStatus};
 use crate::dir::entry::base::{BaseDirEntry, DirEntryReadable};
-use crate::error::FatResult;
+use crate::dir::entry::long::{LongName, short_name_checksum};
+use crate::error::{FatError, FatResult};
 use crate::FatDeviceAccessible;
+use crate::options::MountOptions;
+use crate::time::{FatDate, FatDateTime, FatTime};
+
+pub const NT_LOWERCASE_BASE: u8 = 0x08;
+
+pub const NT_LOWERCASE_EXTENSION: u8 = 0x10;
 
 #[delegate]
 pub trait ShortDirEntryReadable {
     fn name_buff(&self) -> FatResult<[u8; 11]>;
 
 
+    fn nt_reserved(&self) -> FatResult<u8>;
+
+
     fn first_cluster_no_hi(&self) -> FatResult<u16>;
 
 
@@ -27,14 +37,63 @@ pub trait ShortDirEntryReadable {
     fn file_size(&self) -> FatResult<u32>;
 
 
+    fn create_time_tenth(&self) -> FatResult<u8>;
+
+
+    fn create_time(&self) -> FatResult<FatTime>;
+
+
+    fn create_date(&self) -> FatResult<FatDate>;
+
+
+    fn last_access_date(&self) -> FatResult<FatDate>;
+
+
+    fn write_time(&self) -> FatResult<FatTime>;
+
+
+    fn write_date(&self) -> FatResult<FatDate>;
+
+
+    /// Like [`ShortDirEntryReadable::modified`], the raw fields are not validated.
+    #[inline]
+    fn created(&self) -> FatResult<FatDateTime> {
+        Ok(FatDateTime::from_raw(self.create_date()?, self.create_time()?, self.create_time_tenth()?))
+    }
+
+
+    #[inline]
+    fn modified(&self) -> FatResult<FatDateTime> {
+        Ok(FatDateTime::new(self.write_date()?, self.write_time()?))
+    }
+
+
+    #[inline]
+    fn is_base_lowercase(&self) -> FatResult<bool> {
+        Ok(self.nt_reserved()? & NT_LOWERCASE_BASE != 0)
+    }
+
+
+    #[inline]
+    fn is_extension_lowercase(&self) -> FatResult<bool> {
+        Ok(self.nt_reserved()? & NT_LOWERCASE_EXTENSION != 0)
+    }
+
+
     #[cfg(feature = "alloc")]
     fn name(&self) -> FatResult<CString> {
-        let buff = self.name_buff()?;
+        let mut buff = self.name_buff()?;
+        if buff[0] == EntryStatus::JapaneseName as u8 {
+            buff[0] = EntryStatus::Deleted as u8;
+        }
+
+        let base_lowercase = self.is_base_lowercase()?;
+        let extension_lowercase = self.is_extension_lowercase()?;
 
         let mut prefix = buff
             .iter()
             .take(8)
-            .copied()
+            .map(|b| if base_lowercase { b.to_ascii_lowercase() } else { *b })
             .collect::<Vec<u8>>();
 
         while prefix.last().map(|b| *b == 0x20).unwrap_or(false) {
@@ -45,9 +104,10 @@ pub trait ShortDirEntryReadable {
             .iter()
             .skip(8)
             .take_while(|b| **b != 0x20)
+            .map(|b| if extension_lowercase { b.to_ascii_lowercase() } else { *b })
             .peekable();
 
-        if suffix.peek().map(|b| **b != 0x20).unwrap_or(false) {
+        if suffix.peek().is_some() {
             prefix.push(0x2E);
             prefix.extend(suffix);
         }
@@ -71,6 +131,63 @@ pub trait ShortDirEntryReadable {
     fn file_size_usize(&self) -> FatResult<usize> {
         Ok(self.file_size()? as usize)
     }
+
+
+    #[inline]
+    fn name_checksum(&self) -> FatResult<u8> {
+        Ok(short_name_checksum(&self.name_buff()?))
+    }
+}
+
+
+#[delegate]
+pub trait ShortDirEntryWritable {
+    fn write_first_cluster_no(&mut self, cluster_no: u32) -> FatResult;
+
+
+    fn write_file_size(&mut self, file_size: u32) -> FatResult;
+
+
+    fn write_created(&mut self, created: FatDateTime) -> FatResult;
+
+
+    fn write_modified(&mut self, modified: FatDateTime) -> FatResult;
+
+
+    fn write_last_access_date(&mut self, date: FatDate) -> FatResult;
+
+
+    fn write_attribute(&mut self, attribute: Attribute) -> FatResult;
+
+
+    fn set_attribute(&mut self, flags: Attribute, enabled: bool) -> FatResult;
+
+
+    #[inline]
+    fn set_read_only(&mut self, enabled: bool) -> FatResult {
+        self.set_attribute(Attribute::READ_ONLY, enabled)
+    }
+
+
+    #[inline]
+    fn set_hidden(&mut self, enabled: bool) -> FatResult {
+        self.set_attribute(Attribute::HIDDEN, enabled)
+    }
+
+
+    #[inline]
+    fn set_system(&mut self, enabled: bool) -> FatResult {
+        self.set_attribute(Attribute::SYSTEM, enabled)
+    }
+
+
+    #[inline]
+    fn set_archive(&mut self, enabled: bool) -> FatResult {
+        self.set_attribute(Attribute::ARCHIVE, enabled)
+    }
+
+
+    fn mark_deleted(&mut self) -> FatResult;
 }
 
 
@@ -80,6 +197,8 @@ pub struct ShortDirEntry<D>
 {
     #[to(DirEntryReadable, BpbReadable, FatDeviceAccessible)]
     pub(crate) base: BaseDirEntry<D>,
+
+    long_name: Option<LongName>,
 }
 
 
@@ -89,17 +208,72 @@ impl<D> ShortDirEntry<D>
     #[inline]
     pub const fn new(base: BaseDirEntry<D>) -> Self {
         Self {
-            base
+            base,
+            long_name: None,
         }
     }
 
 
-    pub fn data(&self) -> FatResult<Data<D>> {
-        if let Attribute::Dir = self.attribute()? {
-            Ok(Data::Dir(DirEntries::from_entry(self.clone(), self.first_cluster_no()? as usize)))
+    #[inline]
+    pub fn with_long_name(self, long_name: Option<LongName>) -> Self {
+        Self {
+            long_name,
+            ..self
+        }
+    }
+
+
+    #[inline]
+    pub fn long_name(&self) -> Option<&str> {
+        self.long_name
+            .as_ref()
+            .map(LongName::name)
+    }
+
+
+    #[inline]
+    pub fn long_entry_offsets(&self) -> &[usize] {
+        self.long_name
+            .as_ref()
+            .map(LongName::entry_offsets)
+            .unwrap_or_default()
+    }
+
+
+    pub fn data(&self, options: MountOptions) -> FatResult<Data<D>> {
+        if self.attribute()?.is_dir() {
+            let cluster_no = match self.first_cluster_no()? {
+                0 => self.root_dir_cluster_no()?,
+                cluster_no => cluster_no as usize
+            };
+            Ok(Data::Dir(DirEntries::from_entry(self.clone(), cluster_no, options)))
         } else {
-            Ok(Data::RegularFile(RegularFile::new(self.clone())))
+            Ok(Data::RegularFile(RegularFile::new(self.clone(), options)))
+        }
+    }
+
+
+    pub(crate) fn touch_modified(&mut self, options: &MountOptions) -> FatResult {
+        let now = options.now();
+        self.write_modified(now)?;
+
+        if options.update_access_date() {
+            self.write_last_access_date(now.date())?;
+        }
+        Ok(())
+    }
+
+
+    pub(crate) fn touch_accessed(&mut self, options: &MountOptions) -> FatResult {
+        if !options.update_access_date() {
+            return Ok(());
         }
+
+        let today = options.now().date();
+        if self.last_access_date()? < today {
+            self.write_last_access_date(today)?;
+        }
+        Ok(())
     }
 
 
@@ -122,6 +296,12 @@ impl<D> ShortDirEntryReadable for ShortDirEntry<D>
     }
 
 
+    #[inline]
+    fn nt_reserved(&self) -> FatResult<u8> {
+        self.base.bpb.read_u8(self.offset(12))
+    }
+
+
     #[inline]
     fn first_cluster_no_hi(&self) -> FatResult<u16> {
         self.base.bpb.read_u16(self.offset(20))
@@ -138,6 +318,145 @@ impl<D> ShortDirEntryReadable for ShortDirEntry<D>
     fn file_size(&self) -> FatResult<u32> {
         self.base.bpb.read_u32(self.offset(28))
     }
+
+
+    #[inline]
+    fn create_time_tenth(&self) -> FatResult<u8> {
+        self.base.bpb.read_u8(self.offset(13))
+    }
+
+
+    #[inline]
+    fn create_time(&self) -> FatResult<FatTime> {
+        Ok(FatTime::from_raw(self.base.bpb.read_u16(self.offset(14))?))
+    }
+
+
+    #[inline]
+    fn create_date(&self) -> FatResult<FatDate> {
+        Ok(FatDate::from_raw(self.base.bpb.read_u16(self.offset(16))?))
+    }
+
+
+    #[inline]
+    fn last_access_date(&self) -> FatResult<FatDate> {
+        Ok(FatDate::from_raw(self.base.bpb.read_u16(self.offset(18))?))
+    }
+
+
+    #[inline]
+    fn write_time(&self) -> FatResult<FatTime> {
+        Ok(FatTime::from_raw(self.base.bpb.read_u16(self.offset(22))?))
+    }
+
+
+    #[inline]
+    fn write_date(&self) -> FatResult<FatDate> {
+        Ok(FatDate::from_raw(self.base.bpb.read_u16(self.offset(24))?))
+    }
+}
+
+
+pub fn new_short_entry_buff(name_buff: &[u8; 11], attribute: u8, nt_reserved: u8, first_cluster_no: u32, now: FatDateTime) -> [u8; 0x20] {
+    let time = now.time().raw().to_le_bytes();
+    let date = now.date().raw().to_le_bytes();
+
+    let mut buff = [0; 0x20];
+    buff[..11].copy_from_slice(name_buff);
+    buff[11] = attribute;
+    buff[12] = nt_reserved;
+    buff[13] = now.centiseconds();
+    buff[14..16].copy_from_slice(&time);
+    buff[16..18].copy_from_slice(&date);
+    buff[18..20].copy_from_slice(&date);
+    buff[20..22].copy_from_slice(&((first_cluster_no >> 16) as u16).to_le_bytes());
+    buff[22..24].copy_from_slice(&time);
+    buff[24..26].copy_from_slice(&date);
+    buff[26..28].copy_from_slice(&(first_cluster_no as u16).to_le_bytes());
+
+    buff
+}
+
+
+impl<D> ShortDirEntryWritable for ShortDirEntry<D>
+    where D: FatDeviceAccessible + Clone + BpbReadable
+{
+    fn write_first_cluster_no(&mut self, cluster_no: u32) -> FatResult {
+        let offset = self.offset(20);
+        self.base.bpb.write_u16(offset, (cluster_no >> 16) as u16)?;
+
+        let offset = self.offset(26);
+        self.base.bpb.write_u16(offset, cluster_no as u16)
+    }
+
+
+    #[inline]
+    fn write_file_size(&mut self, file_size: u32) -> FatResult {
+        let offset = self.offset(28);
+        self.base.bpb.write_u32(offset, file_size)
+    }
+
+
+    fn write_created(&mut self, created: FatDateTime) -> FatResult {
+        let offset = self.offset(13);
+        self.base.bpb.write_u8(offset, created.centiseconds())?;
+
+        let offset = self.offset(14);
+        self.base.bpb.write_u16(offset, created.time().raw())?;
+
+        let offset = self.offset(16);
+        self.base.bpb.write_u16(offset, created.date().raw())
+    }
+
+
+    fn write_modified(&mut self, modified: FatDateTime) -> FatResult {
+        let offset = self.offset(22);
+        self.base.bpb.write_u16(offset, modified.time().raw())?;
+
+        let offset = self.offset(24);
+        self.base.bpb.write_u16(offset, modified.date().raw())
+    }
+
+
+    #[inline]
+    fn write_last_access_date(&mut self, date: FatDate) -> FatResult {
+        let offset = self.offset(18);
+        self.base.bpb.write_u16(offset, date.raw())
+    }
+
+
+    #[inline]
+    fn write_attribute(&mut self, attribute: Attribute) -> FatResult {
+        let offset = self.offset(11);
+        self.base.bpb.write_u8(offset, attribute.bits())
+    }
+
+
+    fn set_attribute(&mut self, flags: Attribute, enabled: bool) -> FatResult {
+        if !Attribute::USER_FLAGS.contains(flags) {
+            return Err(FatError::InvalidAttribute(flags.bits()));
+        }
+
+        let mut attribute = self.attribute()?;
+        attribute.set(flags, enabled);
+        self.write_attribute(attribute)
+    }
+
+
+    fn mark_deleted(&mut self) -> FatResult {
+        let offsets = self
+            .long_entry_offsets()
+            .iter()
+            .copied()
+            .chain([self.base.offset])
+            .collect::<Vec<usize>>();
+
+        for offset in offsets {
+            self.base.bpb.write_u8(offset, EntryStatus::Deleted as u8)?;
+        }
+
+        Ok(())
+    }
 }
 
 
@@ -146,6 +465,7 @@ impl<D> Debug for ShortDirEntry<D> where D: FatDeviceAccessible + Clone + BpbRea
         f
             .debug_struct("DirEntry")
             .field("name_buff", &self.name())
+            .field("long_name", &self.long_name)
             .field("first_cluster_no", &self.first_cluster_no())
             .field("file_size", &self.file_size())
             .field("attribute", &self.attribute())
@@ -156,15 +476,22 @@ impl<D> Debug for ShortDirEntry<D> where D: FatDeviceAccessible + Clone + BpbRea
 
 #[cfg(test)]
 mod tests {
-    use crate::bpb::BpbFat32;
+    use alloc::ffi::CString;
+
+    use crate::Fat;
+    use crate::bpb::Bpb;
     use crate::dir::entry::Attribute;
     use crate::dir::entry::base::DirEntryReadable;
-    use crate::dir::entry::short::ShortDirEntryReadable;
-    use crate::test::file_device;
+    use crate::dir::entry::short::{NT_LOWERCASE_BASE, NT_LOWERCASE_EXTENSION, ShortDirEntryReadable, ShortDirEntryWritable};
+    use crate::error::FatError;
+    use crate::FatDeviceAccessible;
+    use crate::test::{file_device, memory_device};
+    use crate::time::{FatDate, FatTime};
 
     #[test]
     fn it_volume_label_name() {
-        let mut root = BpbFat32::new(file_device())
+        let mut root = Bpb::new(file_device())
+            .unwrap()
             .root_dir()
             .unwrap();
 
@@ -182,7 +509,8 @@ mod tests {
 
     #[test]
     fn it_hello_txt_file_name() {
-        let file_name = BpbFat32::new(file_device())
+        let file_name = Bpb::new(file_device())
+            .unwrap()
             .root_dir()
             .unwrap()
             .find("HELLO.TXT")
@@ -196,7 +524,8 @@ mod tests {
 
     #[test]
     fn it_volume_label_attribute() {
-        let volume_label = BpbFat32::new(file_device())
+        let volume_label = Bpb::new(file_device())
+            .unwrap()
             .root_dir()
             .unwrap()
             .next()
@@ -204,16 +533,82 @@ mod tests {
             .into_regular_file()
             .unwrap();
 
-        assert_eq!(volume_label.attribute(), Ok(Attribute::VolumeLabel));
+        assert_eq!(volume_label.attribute(), Ok(Attribute::VOLUME_LABEL));
+        assert!(volume_label.attribute().unwrap().is_volume_label());
     }
 
 
     #[test]
     fn it_dir_fist_cluster_is_zero_if_volume_label() {
-        let mut root = BpbFat32::new(file_device())
+        let mut root = Bpb::new(file_device())
+            .unwrap()
             .root_dir()
             .unwrap();
 
         assert_eq!(root.next().unwrap().into_regular_file().unwrap().first_cluster_no(), Ok(0x00));
     }
-}
\ No newline at end of file
+
+
+    fn hello_txt_name_with_nt_reserved(nt_reserved: u8) -> CString {
+        let mut device = memory_device();
+        device.write(&[nt_reserved], 0x102040 + 12).unwrap();
+
+        Bpb::new(device)
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("HELLO.TXT")
+            .unwrap()
+            .name()
+            .unwrap()
+    }
+
+
+    #[test]
+    fn it_lowercase_base_and_extension() {
+        assert_eq!(hello_txt_name_with_nt_reserved(NT_LOWERCASE_BASE | NT_LOWERCASE_EXTENSION).to_str(), Ok("hello.txt"));
+    }
+
+
+    #[test]
+    fn it_lowercase_base() {
+        assert_eq!(hello_txt_name_with_nt_reserved(NT_LOWERCASE_BASE).to_str(), Ok("hello.TXT"));
+    }
+
+
+    #[test]
+    fn it_lowercase_extension() {
+        assert_eq!(hello_txt_name_with_nt_reserved(NT_LOWERCASE_EXTENSION).to_str(), Ok("HELLO.txt"));
+    }
+
+
+    #[test]
+    fn it_hello_txt_timestamps() {
+        let hello = Bpb::new(file_device())
+            .unwrap()
+            .root_dir()
+            .unwrap()
+            .find("HELLO.TXT")
+            .unwrap();
+
+        assert_eq!(hello.last_access_date(), Ok(FatDate::new(2023, 7, 13).unwrap()));
+        assert_eq!(hello.modified().unwrap().to_unix_seconds(), Ok(1_689_157_384));
+        assert_eq!(hello.created().unwrap().centiseconds(), 51);
+        assert_eq!(hello.created().unwrap().time(), FatTime::new(10, 23, 4).unwrap());
+    }
+
+
+    #[test]
+    fn it_set_attributes() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        let mut hello = fat.open("/HELLO.TXT").unwrap();
+        hello.set_read_only(true).unwrap();
+        hello.set_hidden(true).unwrap();
+        hello.set_archive(false).unwrap();
+        fat.open("/TEST").unwrap().set_system(true).unwrap();
+
+        assert_eq!(fat.open("/HELLO.TXT").unwrap().attribute(), Ok(Attribute::READ_ONLY | Attribute::HIDDEN));
+        assert_eq!(fat.open("/TEST").unwrap().attribute(), Ok(Attribute::DIR | Attribute::SYSTEM));
+        assert_eq!(hello.set_attribute(Attribute::DIR, true), Err(FatError::InvalidAttribute(0x10)));
+    }
+}
@@ -0,0 +1,216 @@
+use alloc::format;
+
+use crate::dir::entry::short::{NT_LOWERCASE_BASE, NT_LOWERCASE_EXTENSION};
+
+const SHORT_NAME_SPECIAL_CHARS: &[u8] = b"$%'-_@~`!(){}^#&";
+
+const LONG_NAME_INVALID_CHARS: &str = "\"*/:<>?\\|";
+
+const MAX_LONG_NAME_UNITS: usize = 255;
+
+
+/// Only ASCII is upper-cased; a query with a character that cannot appear in a short name never matches.
+pub fn eq_short_name(short_name: &[u8], name: &str) -> bool {
+    name.bytes().all(|b| matches!(b, b'.' | b' ') || is_short_name_char(b.to_ascii_uppercase()))
+        && short_name.eq_ignore_ascii_case(name.as_bytes())
+}
+
+
+#[inline]
+pub fn eq_long_name(long_name: &str, name: &str) -> bool {
+    long_name
+        .chars()
+        .map(fold_case)
+        .eq(name.chars().map(fold_case))
+}
+
+
+fn fold_case(c: char) -> char {
+    let mut lower = c.to_lowercase();
+
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => c
+    }
+}
+
+
+/// Trailing dots and spaces are not part of a VFAT name.
+#[inline]
+pub fn trim_long_name(name: &str) -> &str {
+    name.trim_end_matches(['.', ' '])
+}
+
+
+pub fn is_valid_long_name(name: &str) -> bool {
+    let name = trim_long_name(name);
+
+    !name.is_empty()
+        && name.encode_utf16().count() <= MAX_LONG_NAME_UNITS
+        && !name
+        .chars()
+        .any(|c| c < ' ' || LONG_NAME_INVALID_CHARS.contains(c))
+}
+
+
+pub fn fit_short_name(name: &str) -> Option<([u8; 11], u8)> {
+    let (base, extension) = name.split_once('.').unwrap_or((name, ""));
+    if base.is_empty() || 8 < base.len() || 3 < extension.len() || name.ends_with('.') || extension.contains('.') {
+        return None;
+    }
+
+    let nt_reserved = case_flag(base, NT_LOWERCASE_BASE)? | case_flag(extension, NT_LOWERCASE_EXTENSION)?;
+    let mut buff = [0x20; 11];
+    let (base_buff, extension_buff) = buff.split_at_mut(8);
+    for (b, c) in base_buff.iter_mut().zip(base.bytes()).chain(extension_buff.iter_mut().zip(extension.bytes())) {
+        *b = c.to_ascii_uppercase();
+    }
+
+    base
+        .bytes()
+        .chain(extension.bytes())
+        .all(|c| is_short_name_char(c.to_ascii_uppercase()))
+        .then_some((buff, nt_reserved))
+}
+
+
+pub fn basis_name(name: &str) -> [u8; 11] {
+    let name = name.trim_start_matches('.');
+    let (base, extension) = name.rsplit_once('.').unwrap_or((name, ""));
+    let mut buff = [0x20; 11];
+    for (b, c) in buff[..8].iter_mut().zip(basis_chars(base)) {
+        *b = c;
+    }
+    for (b, c) in buff[8..].iter_mut().zip(basis_chars(extension)) {
+        *b = c;
+    }
+
+    buff
+}
+
+
+pub fn with_numeric_tail(basis: &[u8; 11], n: usize) -> [u8; 11] {
+    let tail = format!("~{n}");
+    let base_len = basis[..8]
+        .iter()
+        .rposition(|b| *b != 0x20)
+        .map(|i| i + 1)
+        .unwrap_or(0)
+        .min(8 - tail.len());
+
+    let mut buff = *basis;
+    buff[base_len..(base_len + tail.len())].copy_from_slice(tail.as_bytes());
+    buff[(base_len + tail.len())..8].fill(0x20);
+    buff
+}
+
+
+fn basis_chars(part: &str) -> impl Iterator<Item=u8> + '_ {
+    part
+        .chars()
+        .filter(|c| *c != ' ' && *c != '.')
+        .map(short_name_char)
+}
+
+
+fn short_name_char(c: char) -> u8 {
+    let b = if c.is_ascii() { c.to_ascii_uppercase() as u8 } else { b'_' };
+    if is_short_name_char(b) { b } else { b'_' }
+}
+
+
+fn case_flag(part: &str, flag: u8) -> Option<u8> {
+    let lowercase = part.bytes().any(|b| b.is_ascii_lowercase());
+    let uppercase = part.bytes().any(|b| b.is_ascii_uppercase());
+
+    match (lowercase, uppercase) {
+        (true, true) => None,
+        (true, false) => Some(flag),
+        _ => Some(0)
+    }
+}
+
+
+#[inline]
+fn is_short_name_char(b: u8) -> bool {
+    b.is_ascii_uppercase() || b.is_ascii_digit() || SHORT_NAME_SPECIAL_CHARS.contains(&b)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::dir::entry::short::{NT_LOWERCASE_BASE, NT_LOWERCASE_EXTENSION};
+    use crate::dir::name::{basis_name, eq_long_name, eq_short_name, fit_short_name, is_valid_long_name, trim_long_name, with_numeric_tail};
+
+    #[test]
+    fn it_eq_short_name_ignore_case() {
+        assert!(eq_short_name(b"HELLO.TXT", "hello.txt"));
+        assert!(eq_short_name(b"HELLO.TXT", "Hello.Txt"));
+        assert!(!eq_short_name(b"HELLO.TXT", "hello.tx"));
+    }
+
+
+    #[test]
+    fn it_not_eq_short_name_with_invalid_short_name_chars() {
+        assert!(!eq_short_name(b"A_B.TXT", "a+b.txt"));
+        assert!(!eq_short_name(b"CAF_.TXT", "café.txt"));
+        assert!(eq_short_name(b"A_B.TXT", "a_b.txt"));
+    }
+
+
+    #[test]
+    fn it_eq_long_name_ignore_case() {
+        assert!(eq_long_name("Straße.txt", "STRAßE.TXT"));
+        assert!(eq_long_name("ΣΟΦΙΑ", "σοφια"));
+        assert!(eq_long_name("\u{212A}elvin", "kelvin"));
+        assert!(!eq_long_name("hello.txt", "hello.txt2"));
+    }
+
+
+    #[test]
+    fn it_fit_short_name() {
+        assert_eq!(fit_short_name("HELLO.TXT"), Some((*b"HELLO   TXT", 0)));
+        assert_eq!(fit_short_name("readme.txt"), Some((*b"README  TXT", NT_LOWERCASE_BASE | NT_LOWERCASE_EXTENSION)));
+        assert_eq!(fit_short_name("README.txt"), Some((*b"README  TXT", NT_LOWERCASE_EXTENSION)));
+        assert_eq!(fit_short_name("MAKEFILE"), Some((*b"MAKEFILE   ", 0)));
+    }
+
+
+    #[test]
+    fn it_not_fit_short_name() {
+        assert_eq!(fit_short_name("ReadMe.txt"), None);
+        assert_eq!(fit_short_name("LONGFILENAME.TXT"), None);
+        assert_eq!(fit_short_name("A.B.C"), None);
+        assert_eq!(fit_short_name("A B.TXT"), None);
+        assert_eq!(fit_short_name("HELLO.HTML"), None);
+        assert_eq!(fit_short_name(".GITIGNORE"), None);
+    }
+
+
+    #[test]
+    fn it_basis_name() {
+        assert_eq!(&basis_name("The quick brown.fox"), b"THEQUICKFOX");
+        assert_eq!(&basis_name(".gitignore"), b"GITIGNOR   ");
+        assert_eq!(&basis_name("a+b.tar.gz"), b"A_BTAR  GZ ");
+    }
+
+
+    #[test]
+    fn it_with_numeric_tail() {
+        assert_eq!(&with_numeric_tail(b"THEQUICKFOX", 1), b"THEQUI~1FOX");
+        assert_eq!(&with_numeric_tail(b"AB      TXT", 12), b"AB~12   TXT");
+    }
+
+
+    #[test]
+    fn it_valid_long_name() {
+        assert!(is_valid_long_name("a long file name.txt"));
+        assert!(!is_valid_long_name(".."));
+        assert!(!is_valid_long_name("a:b"));
+        assert!(!is_valid_long_name(""));
+        assert!(!is_valid_long_name("   "));
+        assert!(!is_valid_long_name("..."));
+        assert!(is_valid_long_name("foo."));
+        assert_eq!(trim_long_name("foo. . "), "foo");
+    }
+}
@@ -0,0 +1,51 @@
+#[inline]
+pub fn components(path: &str) -> impl Iterator<Item=&str> {
+    path
+        .split(['/', '\\'])
+        .filter(|name| !name.is_empty())
+}
+
+
+pub fn split_parent(path: &str) -> (&str, &str) {
+    let path = path.trim_end_matches(['/', '\\']);
+
+    match path.rfind(['/', '\\']) {
+        Some(i) => (&path[..i], &path[(i + 1)..]),
+        None => ("", path)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::dir::path::{components, split_parent};
+
+    #[test]
+    fn it_split_components() {
+        let names = components("/BOOT/EFI//BOOTX64.EFI").collect::<Vec<_>>();
+        assert_eq!(names, ["BOOT", "EFI", "BOOTX64.EFI"]);
+    }
+
+
+    #[test]
+    fn it_split_back_slash_components() {
+        let names = components("\\BOOT\\EFI").collect::<Vec<_>>();
+        assert_eq!(names, ["BOOT", "EFI"]);
+    }
+
+
+    #[test]
+    fn it_root_has_no_components() {
+        assert_eq!(components("/").count(), 0);
+    }
+
+
+    #[test]
+    fn it_split_parent() {
+        assert_eq!(split_parent("/BOOT/EFI/BOOTX64.EFI"), ("/BOOT/EFI", "BOOTX64.EFI"));
+        assert_eq!(split_parent("HELLO.TXT"), ("", "HELLO.TXT"));
+        assert_eq!(split_parent("/TEST/"), ("", "TEST"));
+    }
+}
@@ -1,12 +1,13 @@
 use alloc::string::String;
-use num_enum::TryFromPrimitiveError;
 use thiserror_no_std::Error;
 
-use crate::dir::entry::Attribute;
+use crate::bpb::FatType;
+use crate::gpt::PartitionSelector;
 
 #[derive(Debug, PartialEq)]
 pub enum FatDeviceError {
-    StatusCode(isize)
+    StatusCode(isize),
+    OutOfRange(usize),
 }
 
 
@@ -32,7 +33,121 @@ pub enum FatError {
 
 
     #[error("Not found file {0}")]
-    NotfoundFile(String)
+    NotfoundFile(String),
+
+    #[error("Cluster {0} in the chain is marked as bad")]
+    BadClusterInChain(usize),
+
+    #[error("Cluster {0} in the chain is marked as free")]
+    FreeClusterInChain(usize),
+
+    #[error("Reserved value in the fat entry = {0:#X}")]
+    InvalidFatEntry(u32),
+
+    #[error("Cluster {0} is out of range")]
+    InvalidClusterNo(usize),
+
+    #[error("Cluster chain loops back at cluster {0}")]
+    CyclicClusterChain(usize),
+
+    #[error("Cluster chain ended after {0} bytes of {1} bytes")]
+    UnexpectedEndOfChain(usize, usize),
+
+    #[error("Write ends at {0} but file size is {1}")]
+    WriteBeyondFileSize(usize, usize),
+
+    #[error("No free cluster left on the volume")]
+    NoFreeCluster,
+
+    #[error("File size must be 4GiB or less, but was {0}")]
+    FileTooLarge(usize),
+
+    #[error("Already exists {0}")]
+    AlreadyExists(String),
+
+    #[error("Invalid file name {0}")]
+    InvalidFileName(String),
+
+    #[error("{0} is a directory")]
+    IsDirectory(String),
+
+    #[error("{0} is read only")]
+    ReadOnly(String),
+
+    #[error("{0} is a volume label")]
+    IsVolumeLabel(String),
+
+    #[error("The root directory has no directory entry")]
+    RootDirHasNoEntry,
+
+    #[error("{0} is not a directory")]
+    NotDirectory(String),
+
+    #[error("Directory {0} is not empty")]
+    DirectoryNotEmpty(String),
+
+    #[error("Cannot move {0} into itself")]
+    MoveIntoItself(String),
+
+    #[error("Root directory has no free entries")]
+    RootDirFull,
+
+    #[error("Boot signature must be 0xAA55, but was 0x{0:04X}")]
+    InvalidBootSignature(u16),
+
+    #[error("'bytes per sector' must be one value of 512,1024,2048 or 4096, but was {0}")]
+    InvalidBytesPerSector(u16),
+
+    #[error("'number of fats' must not be 0")]
+    InvalidNumFats,
+
+    #[error("'reserved sectors' must not be 0")]
+    InvalidReservedSectors,
+
+    #[error("FAT of {0} sectors is too small for {1} clusters")]
+    InvalidSectorsPerFat(usize, usize),
+
+    #[error("Total sectors {0} do not reach the data region at sector {1}")]
+    InvalidTotalSectors(usize, usize),
+
+    #[error("'root entry count' = {0} is invalid for {1:?}")]
+    InvalidRootEntryCount(u16, FatType),
+
+    #[error("Root cluster {0} is out of range")]
+    InvalidRootCluster(u32),
+
+    #[error("Block size must not be 0")]
+    InvalidBlockSize,
+
+    #[error("Not found partition {0}")]
+    NotfoundPartition(usize),
+
+    #[error("Partition type 0x{0:02X} is not FAT")]
+    NotFatPartition(u8),
+
+    #[error("Partition table is broken")]
+    InvalidPartitionTable,
+
+    #[error("Protective MBR is missing")]
+    MissingProtectiveMbr,
+
+    #[error("GPT header at LBA {0} is broken")]
+    InvalidGptHeader(usize),
+
+    #[error("Not found GPT partition {0:?}")]
+    NotfoundGptPartition(PartitionSelector),
+
+    #[error("Invalid date {0:04}-{1:02}-{2:02}")]
+    InvalidDate(u16, u8, u8),
+
+    #[error("Invalid time {0:02}:{1:02}:{2:02}")]
+    InvalidTime(u8, u8, u8),
+
+    #[error("Invalid creation time tenth {0}")]
+    InvalidCentiseconds(u8),
+
+    #[error("Unix time {0} is out of the FAT range")]
+    TimestampOutOfRange(u64),
 }
 
 
@@ -42,9 +157,3 @@ impl From<FatDeviceError> for FatError {
     }
 }
 
-
-impl From<TryFromPrimitiveError<Attribute>> for FatError {
-    fn from(value: TryFromPrimitiveError<Attribute>) -> Self {
-        Self::InvalidAttribute(value.number)
-    }
-}
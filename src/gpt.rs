@@ -0,0 +1,530 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+
+use crate::bpb::{buff_read_u16, buff_read_u32};
+use crate::device::offset::OffsetDevice;
+use crate::error::{FatError, FatResult};
+use crate::FatDeviceAccessible;
+use crate::mbr::SECTOR_SIZE;
+
+pub const EFI_SYSTEM_PARTITION: Guid = Guid::from_fields(0xC12A7328, 0xF81F, 0x11D2, [0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B]);
+
+pub const MICROSOFT_BASIC_DATA: Guid = Guid::from_fields(0xEBD0A0A2, 0xB9E5, 0x4433, [0x87, 0xC0, 0x68, 0xB6, 0xB7, 0x26, 0x99, 0xC7]);
+
+const PROTECTIVE_MBR_TYPE: u8 = 0xEE;
+
+const PRIMARY_HEADER_LBA: usize = 1;
+
+const HEADER_SIGNATURE: &[u8; 8] = b"EFI PART";
+
+const MIN_HEADER_SIZE: usize = 92;
+
+const MAX_ENTRIES_BYTES: usize = 0x10_0000;
+
+const LOGICAL_BLOCK_SIZES: [usize; 2] = [SECTOR_SIZE, 0x1000];
+
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Guid([u8; 16]);
+
+
+impl Guid {
+    pub const ZERO: Guid = Guid([0; 16]);
+
+
+    pub const fn from_fields(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> Guid {
+        let data1 = data1.to_le_bytes();
+        let data2 = data2.to_le_bytes();
+        let data3 = data3.to_le_bytes();
+
+        Guid([
+            data1[0], data1[1], data1[2], data1[3],
+            data2[0], data2[1],
+            data3[0], data3[1],
+            data4[0], data4[1], data4[2], data4[3], data4[4], data4[5], data4[6], data4[7],
+        ])
+    }
+
+
+    #[inline]
+    pub const fn from_bytes(bytes: [u8; 16]) -> Guid {
+        Self(bytes)
+    }
+
+
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+
+impl Debug for Guid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let b = &self.0;
+        write!(
+            f,
+            "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+            buff_read_u32(b, 0), buff_read_u16(b, 4), buff_read_u16(b, 6),
+            b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]
+        )
+    }
+}
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum PartitionSelector {
+    Index(usize),
+    Type(Guid),
+    Unique(Guid),
+}
+
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GptPartition {
+    index: usize,
+
+    type_guid: Guid,
+
+    unique_guid: Guid,
+
+    first_lba: usize,
+
+    last_lba: usize,
+
+    attributes: u64,
+
+    name: String,
+
+    block_size: usize,
+}
+
+
+impl GptPartition {
+    fn parse(entry: &[u8], index: usize, block_size: usize) -> GptPartition {
+        let guid = |offset: usize| {
+            let mut bytes = [0; 16];
+            bytes.copy_from_slice(&entry[offset..(offset + 16)]);
+            Guid::from_bytes(bytes)
+        };
+        let read_u64 = |offset: usize| {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(&entry[offset..(offset + 8)]);
+            u64::from_le_bytes(bytes)
+        };
+
+        let units = entry[56..128]
+            .chunks(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+            .take_while(|c| *c != 0x0000);
+
+        Self {
+            index,
+            type_guid: guid(0),
+            unique_guid: guid(16),
+            first_lba: read_u64(32) as usize,
+            last_lba: read_u64(40) as usize,
+            attributes: read_u64(48),
+            name: char::decode_utf16(units)
+                .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                .collect(),
+            block_size,
+        }
+    }
+
+
+    #[inline]
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+
+    #[inline]
+    pub const fn type_guid(&self) -> Guid {
+        self.type_guid
+    }
+
+
+    #[inline]
+    pub const fn unique_guid(&self) -> Guid {
+        self.unique_guid
+    }
+
+
+    #[inline]
+    pub const fn first_lba(&self) -> usize {
+        self.first_lba
+    }
+
+
+    #[inline]
+    pub const fn last_lba(&self) -> usize {
+        self.last_lba
+    }
+
+
+    #[inline]
+    pub const fn attributes(&self) -> u64 {
+        self.attributes
+    }
+
+
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+
+    #[inline]
+    pub const fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.first_lba * self.block_size
+    }
+
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        (self.last_lba + 1 - self.first_lba) * self.block_size
+    }
+
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.last_lba < self.first_lba
+    }
+
+
+    pub fn is_selected_by(&self, selector: &PartitionSelector) -> bool {
+        match selector {
+            PartitionSelector::Index(index) => self.index == *index,
+            PartitionSelector::Type(guid) => self.type_guid == *guid,
+            PartitionSelector::Unique(guid) => self.unique_guid == *guid,
+        }
+    }
+}
+
+
+pub fn is_protective_mbr<D>(device: &D) -> FatResult<bool>
+    where D: FatDeviceAccessible
+{
+    Ok(protective_mbr_last_lba(device)?.is_some())
+}
+
+
+/// The logical block size is probed from the primary header; if it is broken, 512 bytes is assumed.
+pub fn partitions<D>(device: &D) -> FatResult<Vec<GptPartition>>
+    where D: FatDeviceAccessible
+{
+    let last_lba = protective_mbr_last_lba(device)?.ok_or(FatError::MissingProtectiveMbr)?;
+    let block_size = probe_block_size(device)?;
+    let alternate_lba = match read_header(device, PRIMARY_HEADER_LBA, block_size) {
+        Ok(header) => match read_entries(device, &header, PRIMARY_HEADER_LBA, block_size) {
+            Err(FatError::InvalidGptHeader(_)) => Some(read_u64(&header, 32)),
+            entries => return Ok(used_partitions(entries?))
+        },
+        Err(FatError::InvalidGptHeader(_)) => None,
+        Err(e) => return Err(e)
+    };
+
+    // The protective MBR only approximates the end of the disk, so it is the last place to look for the backup header.
+    let entries = match alternate_lba {
+        Some(alternate_lba) if alternate_lba != last_lba => match read_table(device, alternate_lba, block_size) {
+            Err(FatError::InvalidGptHeader(_)) => read_table(device, last_lba, block_size)?,
+            entries => entries?
+        },
+        _ => read_table(device, last_lba, block_size)?
+    };
+
+    Ok(used_partitions(entries))
+}
+
+
+pub fn partition<D>(device: D, selector: PartitionSelector) -> FatResult<OffsetDevice<D>>
+    where D: FatDeviceAccessible
+{
+    let partition = partitions(&device)?
+        .into_iter()
+        .find(|partition| partition.is_selected_by(&selector))
+        .ok_or(FatError::NotfoundGptPartition(selector))?;
+
+    Ok(OffsetDevice::new(device, partition.offset(), partition.len()))
+}
+
+
+#[inline]
+pub fn efi_system_partition<D>(device: D) -> FatResult<OffsetDevice<D>>
+    where D: FatDeviceAccessible
+{
+    partition(device, PartitionSelector::Type(EFI_SYSTEM_PARTITION))
+}
+
+
+pub fn crc32(buff: &[u8]) -> u32 {
+    !buff
+        .iter()
+        .fold(!0u32, |crc, b| {
+            (0..8).fold(crc ^ *b as u32, |crc, _| {
+                if crc & 1 == 0 { crc >> 1 } else { (crc >> 1) ^ 0xEDB8_8320 }
+            })
+        })
+}
+
+
+fn protective_mbr_last_lba<D>(device: &D) -> FatResult<Option<usize>>
+    where D: FatDeviceAccessible
+{
+    let mut sector = [0; SECTOR_SIZE];
+    device.read(&mut sector, 0, SECTOR_SIZE)?;
+    if sector[510..512] != [0x55, 0xAA] {
+        return Ok(None);
+    }
+
+    let last_lba = sector[0x1BE..0x1FE]
+        .chunks(16)
+        .find(|entry| entry[4] == PROTECTIVE_MBR_TYPE && 0 < buff_read_u32(entry, 12))
+        .map(|entry| buff_read_u32(entry, 8) as usize + buff_read_u32(entry, 12) as usize - 1);
+
+    Ok(last_lba)
+}
+
+
+fn probe_block_size<D>(device: &D) -> FatResult<usize>
+    where D: FatDeviceAccessible
+{
+    for block_size in LOGICAL_BLOCK_SIZES {
+        match read_header(device, PRIMARY_HEADER_LBA, block_size) {
+            Ok(_) => return Ok(block_size),
+            Err(FatError::InvalidGptHeader(_)) => {}
+            Err(e) => return Err(e)
+        }
+    }
+
+    Ok(SECTOR_SIZE)
+}
+
+
+fn read_table<D>(device: &D, header_lba: usize, block_size: usize) -> FatResult<Vec<GptPartition>>
+    where D: FatDeviceAccessible
+{
+    let header = read_header(device, header_lba, block_size)?;
+    read_entries(device, &header, header_lba, block_size)
+}
+
+
+fn read_header<D>(device: &D, header_lba: usize, block_size: usize) -> FatResult<[u8; SECTOR_SIZE]>
+    where D: FatDeviceAccessible
+{
+    let mut header = [0; SECTOR_SIZE];
+    device.read(&mut header, header_lba * block_size, SECTOR_SIZE)?;
+
+    let header_size = buff_read_u32(&header, 12) as usize;
+    if &header[..8] != HEADER_SIGNATURE || !(MIN_HEADER_SIZE..=SECTOR_SIZE).contains(&header_size) {
+        return Err(FatError::InvalidGptHeader(header_lba));
+    }
+
+    let header_crc = buff_read_u32(&header, 16);
+    let mut zeroed = header;
+    zeroed[16..20].fill(0);
+    if crc32(&zeroed[..header_size]) != header_crc || read_u64(&header, 24) != header_lba {
+        return Err(FatError::InvalidGptHeader(header_lba));
+    }
+
+    Ok(header)
+}
+
+
+fn read_entries<D>(device: &D, header: &[u8; SECTOR_SIZE], header_lba: usize, block_size: usize) -> FatResult<Vec<GptPartition>>
+    where D: FatDeviceAccessible
+{
+    let entries_lba = read_u64(header, 72);
+    let entry_count = buff_read_u32(header, 80) as usize;
+    let entry_size = buff_read_u32(header, 84) as usize;
+    let entries_bytes = entry_count
+        .checked_mul(entry_size)
+        .filter(|entries_bytes| *entries_bytes <= MAX_ENTRIES_BYTES)
+        .ok_or(FatError::InvalidGptHeader(header_lba))?;
+    if entry_size < 128 {
+        return Err(FatError::InvalidGptHeader(header_lba));
+    }
+
+    let mut entries = vec![0; entries_bytes];
+    device.read(&mut entries, entries_lba * block_size, entries_bytes)?;
+    if crc32(&entries) != buff_read_u32(header, 88) {
+        return Err(FatError::InvalidGptHeader(header_lba));
+    }
+
+    Ok(entries
+        .chunks(entry_size)
+        .enumerate()
+        .map(|(i, entry)| GptPartition::parse(entry, i + 1, block_size))
+        .collect())
+}
+
+
+fn used_partitions(entries: Vec<GptPartition>) -> Vec<GptPartition> {
+    entries
+        .into_iter()
+        .filter(|partition| partition.type_guid != Guid::ZERO && !partition.is_empty())
+        .collect()
+}
+
+
+#[inline]
+fn read_u64(buff: &[u8], index: usize) -> usize {
+    (buff_read_u32(buff, index) as u64 | (buff_read_u32(buff, index + 4) as u64) << 32) as usize
+}
+
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{Fat, FatDeviceAccessible};
+    use crate::error::{FatDeviceError, FatError};
+    use crate::gpt::{crc32, efi_system_partition, EFI_SYSTEM_PARTITION, Guid, is_protective_mbr, MICROSOFT_BASIC_DATA, partition, PartitionSelector, partitions, read_entries};
+    use crate::test::{gpt_4k_memory_device, gpt_memory_device, GPT_BACKUP_HEADER_LBA, memory_device, MemoryDevice, write_partition_entry};
+
+    #[derive(Clone)]
+    struct BrokenPrimaryHeaderDevice(MemoryDevice);
+
+    impl FatDeviceAccessible for BrokenPrimaryHeaderDevice {
+        fn read(&self, buff: &mut [u8], offset: usize, bytes: usize) -> Result<(), FatDeviceError> {
+            if offset == 0x200 {
+                return Err(FatDeviceError::StatusCode(-1));
+            }
+            self.0.read(buff, offset, bytes)
+        }
+
+
+        fn write(&mut self, buff: &[u8], offset: usize) -> Result<(), FatDeviceError> {
+            self.0.write(buff, offset)
+        }
+    }
+
+
+    #[test]
+    fn it_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+
+    #[test]
+    fn it_format_guid() {
+        assert_eq!(alloc::format!("{:?}", EFI_SYSTEM_PARTITION), "C12A7328-F81F-11D2-BA4B-00A0C93EC93B");
+    }
+
+
+    #[test]
+    fn it_detect_protective_mbr() {
+        assert_eq!(is_protective_mbr(&gpt_memory_device()), Ok(true));
+        assert_eq!(is_protective_mbr(&memory_device()), Ok(false));
+        assert_eq!(partitions(&memory_device()), Err(FatError::MissingProtectiveMbr));
+    }
+
+
+    #[test]
+    fn it_parse_partition_entries() {
+        let partitions = partitions(&gpt_memory_device())
+            .unwrap()
+            .into_iter()
+            .map(|partition| (partition.index(), partition.type_guid(), partition.first_lba(), partition.last_lba(), partition.name().into()))
+            .collect::<Vec<(usize, Guid, usize, usize, alloc::string::String)>>();
+
+        assert_eq!(partitions, [
+            (1, EFI_SYSTEM_PARTITION, 34, 2913, "EFI system partition".into()),
+            (3, MICROSOFT_BASIC_DATA, 2914, 5793, "data".into()),
+        ]);
+    }
+
+
+    #[test]
+    fn it_mount_efi_system_partition() {
+        let device = gpt_memory_device();
+        let esp = Fat::mount(efi_system_partition(device.clone()).unwrap()).unwrap();
+        esp.create_dir("/EFI").unwrap();
+
+        let data = Fat::mount(partition(device, PartitionSelector::Index(3)).unwrap()).unwrap();
+        assert!(esp.open("/EFI").is_ok());
+        assert!(data.open("/EFI").is_err());
+    }
+
+
+    #[test]
+    fn it_select_by_unique_guid() {
+        let device = gpt_memory_device();
+        let unique = partitions(&device).unwrap()[1].unique_guid();
+        let selected = partition(device.clone(), PartitionSelector::Unique(unique)).unwrap();
+
+        assert_eq!(selected.offset(), 2914 * 0x200);
+        assert_eq!(
+            partition(device, PartitionSelector::Index(2)).err().unwrap(),
+            FatError::NotfoundGptPartition(PartitionSelector::Index(2))
+        );
+    }
+
+
+    #[test]
+    fn it_fallback_to_backup_header() {
+        let mut device = gpt_memory_device();
+        device.write(&[0xFF], 0x200 + 24).unwrap();
+
+        assert_eq!(partitions(&device).unwrap().len(), 2);
+    }
+
+
+    #[test]
+    fn it_failed_parse_if_both_headers_are_broken() {
+        let mut device = gpt_memory_device();
+        device.write(&[0xFF], 2 * 0x200).unwrap();
+        device.write(&[0xFF], GPT_BACKUP_HEADER_LBA * 0x200 + 16).unwrap();
+
+        assert_eq!(partitions(&device), Err(FatError::InvalidGptHeader(GPT_BACKUP_HEADER_LBA)));
+    }
+
+
+    #[test]
+    fn it_fallback_to_alternate_lba_if_entries_are_broken() {
+        let mut device = gpt_memory_device();
+        write_partition_entry(&mut device, 0, 0, 0xEE, 1, 100);
+        device.write(&[0xFF], 2 * 0x200).unwrap();
+
+        assert_eq!(partitions(&device).unwrap().len(), 2);
+    }
+
+
+    #[test]
+    fn it_failed_parse_on_device_error() {
+        let device = BrokenPrimaryHeaderDevice(gpt_memory_device());
+
+        assert_eq!(partitions(&device), Err(FatError::FailedDeviceAccess(FatDeviceError::StatusCode(-1))));
+    }
+
+
+    #[test]
+    fn it_mount_partition_on_4k_blocks() {
+        let device = gpt_4k_memory_device();
+        let esp = efi_system_partition(device).unwrap();
+
+        assert_eq!(esp.offset(), 6 * 0x1000);
+        assert!(Fat::mount(esp).unwrap().open("/").is_ok());
+    }
+
+
+    #[test]
+    fn it_failed_read_too_large_entries() {
+        let device = gpt_memory_device();
+        let mut header = [0; 0x200];
+        device.read(&mut header, 0x200, 0x200).unwrap();
+        header[80..88].fill(0xFF);
+
+        assert_eq!(read_entries(&device, &header, 1, 0x200), Err(FatError::InvalidGptHeader(1)));
+    }
+}
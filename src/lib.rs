@@ -2,69 +2,185 @@
 
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 use alloc::string::ToString;
 use core::fmt::{Debug, Formatter};
 
+pub use device::block::{BlockDevice, BlockDeviceAdapter};
+pub use device::cache::{BlockCache, CacheBlock};
+pub use device::offset::OffsetDevice;
 pub use device::FatDeviceAccessible;
 
-use crate::bpb::BpbFat32;
+use crate::bpb::{Bpb, FatType};
 use crate::dir::data::{Data, DataEntries};
+use crate::dir::data::dir::DirEntries;
 use crate::dir::data::file::RegularFile;
+use crate::dir::path;
 use crate::error::{FatError, FatResult};
+use crate::options::MountOptions;
+use crate::table::FatTable;
+use crate::table::stats::VolumeStats;
 
 pub mod error;
 mod device;
 pub mod bpb;
 pub mod dir;
-
-pub struct Fat<D> {
-    device: D,
+pub mod table;
+pub mod mbr;
+pub mod gpt;
+pub mod time;
+pub mod options;
+
+pub struct Fat<D>
+    where D: FatDeviceAccessible
+{
+    bpb: Bpb<D>,
+
+    options: MountOptions,
 }
 
 
 impl<D> Fat<D> where D: FatDeviceAccessible + Clone {
     #[inline]
-    pub const fn new(device: D) -> Fat<D> {
-        Self {
-            device
-        }
+    pub fn mount(device: D) -> FatResult<Fat<D>> {
+        Self::mount_with(device, MountOptions::new())
+    }
+
+
+    #[inline]
+    pub fn mount_with(device: D, options: MountOptions) -> FatResult<Fat<D>> {
+        Ok(Self {
+            bpb: Bpb::new(device)?,
+            options,
+        })
+    }
+
+
+    #[inline]
+    pub fn fat_type(&self) -> FatType {
+        self.bpb.params().fat_type()
     }
 
 
-    pub fn root_dir(&self) -> FatResult<DataEntries<BpbFat32<D>>> {
-         BpbFat32::new(self.device.clone())
-            .root_dir()
+    #[inline]
+    pub fn flush(&mut self) -> FatResult {
+        Ok(self.bpb.flush()?)
+    }
+
+
+    #[inline]
+    pub fn stats(&self) -> FatResult<VolumeStats> {
+        FatTable::new(self.bpb.clone()).stats()
+    }
+
+
+    #[inline]
+    pub fn root_dir(&self) -> FatResult<DataEntries<Bpb<D>>> {
+        Ok(DataEntries::new(DirEntries::root(self.bpb.clone(), self.options.clone())?))
     }
 
 
-    pub fn open_file(&self, file_name: &str) -> FatResult<RegularFile<BpbFat32<D>>> {
-        self.open(file_name)?
+    pub fn open_file(&self, path: &str) -> FatResult<RegularFile<Bpb<D>>> {
+        self.open(path)?
             .into_regular_file()
     }
 
 
-    pub fn open(&self, file_name: &str) -> FatResult<Data<BpbFat32<D>>> {
-        BpbFat32::new(self.device.clone())
-            .root_dir()?
-            .find(file_name)
-            .ok_or(FatError::NotfoundFile(file_name.to_string()))
+    pub fn open(&self, path: &str) -> FatResult<Data<Bpb<D>>> {
+        self.root_dir()?
+            .open(path)
+    }
+
+
+    pub fn remove_file(&self, path: &str) -> FatResult {
+        self.open_removable_file(path)?
+            .remove()
+    }
+
+
+    pub fn force_remove_file(&self, path: &str) -> FatResult {
+        self.open_removable_file(path)?
+            .force_remove()
+    }
+
+
+    pub fn create_file(&self, path: &str) -> FatResult<RegularFile<Bpb<D>>> {
+        let (parent, name) = path::split_parent(path);
+
+        self.open(parent)?
+            .into_dir()?
+            .create_file(name)
+    }
+
+
+    pub fn create_dir(&self, path: &str) -> FatResult<DirEntries<Bpb<D>>> {
+        let (parent, name) = path::split_parent(path);
+
+        self.open(parent)?
+            .into_dir()?
+            .create_dir(name)
+    }
+
+
+    pub fn rename(&self, from: &str, to: &str) -> FatResult<Data<Bpb<D>>> {
+        self.rename_with(from, to, false)
+    }
+
+
+    pub fn rename_overwrite(&self, from: &str, to: &str) -> FatResult<Data<Bpb<D>>> {
+        self.rename_with(from, to, true)
+    }
+
+
+    pub fn remove_dir(&self, path: &str) -> FatResult {
+        match self.open(path)? {
+            Data::Dir(dir) => dir.remove(),
+            Data::RegularFile(_) => Err(FatError::NotDirectory(path.to_string()))
+        }
     }
 }
 
 
-impl<D> Debug for Fat<D> where D: Debug {
+impl<D> Fat<D> where D: FatDeviceAccessible + Clone {
+    fn rename_with(&self, from: &str, to: &str, overwrite: bool) -> FatResult<Data<Bpb<D>>> {
+        let source = self.open(from)?;
+        let (parent, name) = path::split_parent(to);
+
+        self.open(parent)?
+            .into_dir()?
+            .rename_into(source, name, overwrite)
+    }
+
+
+    fn open_removable_file(&self, path: &str) -> FatResult<RegularFile<Bpb<D>>> {
+        match self.open(path)? {
+            Data::RegularFile(file) => Ok(file),
+            Data::Dir(_) => Err(FatError::IsDirectory(path.to_string()))
+        }
+    }
+}
+
+
+impl<D> Debug for Fat<D> where D: FatDeviceAccessible + Debug {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{:?}", &self.device)
+        write!(f, "{:?}", &self.bpb.device)
     }
 }
 
 #[cfg(test)]
 pub mod test {
     use alloc::boxed::Box;
+    use alloc::rc::Rc;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::cell::RefCell;
 
     use crate::{Fat, FatDeviceAccessible};
     use crate::error::FatDeviceError;
+    use crate::gpt::{crc32, EFI_SYSTEM_PARTITION, Guid, MICROSOFT_BASIC_DATA};
+    use crate::time::{FatDate, FatDateTime, FatTime, TimeProvider};
 
     #[derive(Clone, Debug)]
     pub struct FileDevice;
@@ -88,10 +204,29 @@ pub mod test {
     }
 
 
+    #[derive(Clone, Debug)]
+    pub struct MemoryDevice(Rc<RefCell<Vec<u8>>>);
+
+    impl FatDeviceAccessible for MemoryDevice {
+        fn read(&self, buff: &mut [u8], offset: usize, bytes: usize) -> Result<(), FatDeviceError> {
+            buff.copy_from_slice(&self.0.borrow()[offset..(offset + bytes)]);
+
+            Ok(())
+        }
+
+
+        fn write(&mut self, buff: &[u8], offset: usize) -> Result<(), FatDeviceError> {
+            self.0.borrow_mut()[offset..(offset + buff.len())].copy_from_slice(buff);
+
+            Ok(())
+        }
+    }
+
+
     #[inline]
     #[allow(unused)]
     pub(crate) fn open_fat32_file() -> Fat<FileDevice> {
-        Fat::new(FileDevice)
+        Fat::mount(FileDevice).unwrap()
     }
 
 
@@ -106,12 +241,202 @@ pub mod test {
     pub(crate) fn read_fat32_buffer() -> Box<[u8]> {
         std::fs::read("./fat_disk_32").unwrap().into_boxed_slice()
     }
+
+
+    /// Copy of the disk image; writes never reach `./fat_disk_32`.
+    #[inline]
+    #[allow(unused)]
+    pub(crate) fn memory_device() -> MemoryDevice {
+        MemoryDevice(Rc::new(RefCell::new(read_fat32_buffer().into_vec())))
+    }
+
+
+    #[allow(unused)]
+    pub(crate) fn fat12_memory_device() -> MemoryDevice {
+        format_memory_device(2880, 1, 1, 224, 9, &[0xF8, 0xFF, 0xFF])
+    }
+
+
+    #[allow(unused)]
+    pub(crate) fn fat16_memory_device() -> MemoryDevice {
+        format_memory_device(0x8000, 4, 4, 512, 32, &[0xF8, 0xFF, 0xFF, 0xFF])
+    }
+
+
+    fn format_memory_device(
+        total_sectors: u16,
+        sectors_per_cluster: u8,
+        reserved_sectors: u16,
+        root_entry_count: u16,
+        sectors_per_fat: u16,
+        reserved_fat_entries: &[u8],
+    ) -> MemoryDevice {
+        let mut buff = vec![0; total_sectors as usize * 0x200];
+        buff[..3].copy_from_slice(&[0xEB, 0x3C, 0x90]);
+        buff[11..13].copy_from_slice(&0x200u16.to_le_bytes());
+        buff[13] = sectors_per_cluster;
+        buff[14..16].copy_from_slice(&reserved_sectors.to_le_bytes());
+        buff[16] = 2;
+        buff[17..19].copy_from_slice(&root_entry_count.to_le_bytes());
+        buff[19..21].copy_from_slice(&total_sectors.to_le_bytes());
+        buff[21] = 0xF8;
+        buff[22..24].copy_from_slice(&sectors_per_fat.to_le_bytes());
+        buff[510..512].copy_from_slice(&[0x55, 0xAA]);
+
+        for fat_index in 0..2 {
+            let offset = (reserved_sectors + fat_index * sectors_per_fat) as usize * 0x200;
+            buff[offset..(offset + reserved_fat_entries.len())].copy_from_slice(reserved_fat_entries);
+        }
+
+        MemoryDevice(Rc::new(RefCell::new(buff)))
+    }
+
+
+    #[allow(unused)]
+    pub(crate) fn partitioned_memory_device() -> MemoryDevice {
+        let fat12 = fat12_memory_device().0.borrow().clone();
+        let mut device = MemoryDevice(Rc::new(RefCell::new(vec![0; 8651 * 0x200])));
+
+        for lba in [0, 2889, 5770] {
+            device.write(&[0x55, 0xAA], lba * 0x200 + 510).unwrap();
+        }
+        write_partition_entry(&mut device, 0, 0, 0x01, 1, 2880);
+        write_partition_entry(&mut device, 0, 1, 0x83, 2881, 8);
+        write_partition_entry(&mut device, 0, 2, 0x0F, 2889, 5762);
+        write_partition_entry(&mut device, 2889, 0, 0x01, 1, 2880);
+        write_partition_entry(&mut device, 2889, 1, 0x05, 2881, 2881);
+        write_partition_entry(&mut device, 5770, 0, 0x0E, 1, 2880);
+
+        for lba in [1, 2890, 5771] {
+            device.write(&fat12, lba * 0x200).unwrap();
+        }
+
+        device
+    }
+
+
+    pub(crate) const GPT_BACKUP_HEADER_LBA: usize = 5826;
+
+
+    #[allow(unused)]
+    pub(crate) fn gpt_memory_device() -> MemoryDevice {
+        let fat12 = fat12_memory_device().0.borrow().clone();
+        let mut device = MemoryDevice(Rc::new(RefCell::new(vec![0; (GPT_BACKUP_HEADER_LBA + 1) * 0x200])));
+        device.write(&[0x55, 0xAA], 510).unwrap();
+        write_partition_entry(&mut device, 0, 0, 0xEE, 1, GPT_BACKUP_HEADER_LBA as u32);
+
+        let mut entries = vec![0; 128 * 128];
+        write_gpt_entry(&mut entries[..128], EFI_SYSTEM_PARTITION, 34, 2913, "EFI system partition");
+        write_gpt_entry(&mut entries[256..384], MICROSOFT_BASIC_DATA, 2914, 5793, "data");
+        device.write(&entries, 2 * 0x200).unwrap();
+        device.write(&entries, (GPT_BACKUP_HEADER_LBA - 32) * 0x200).unwrap();
+
+        write_gpt_header(&mut device, 0x200, 1, GPT_BACKUP_HEADER_LBA, 2, crc32(&entries));
+        write_gpt_header(&mut device, 0x200, GPT_BACKUP_HEADER_LBA, 1, GPT_BACKUP_HEADER_LBA - 32, crc32(&entries));
+
+        for lba in [34, 2914] {
+            device.write(&fat12, lba * 0x200).unwrap();
+        }
+
+        device
+    }
+
+
+    /// Same layout as `gpt_memory_device`, but with 4KiB logical blocks and only the primary header.
+    #[allow(unused)]
+    pub(crate) fn gpt_4k_memory_device() -> MemoryDevice {
+        let fat12 = fat12_memory_device().0.borrow().clone();
+        let mut device = MemoryDevice(Rc::new(RefCell::new(vec![0; 367 * 0x1000])));
+        device.write(&[0x55, 0xAA], 510).unwrap();
+        write_partition_entry(&mut device, 0, 0, 0xEE, 1, 366);
+
+        let mut entries = vec![0; 128 * 128];
+        write_gpt_entry(&mut entries[..128], EFI_SYSTEM_PARTITION, 6, 365, "EFI system partition");
+        device.write(&entries, 2 * 0x1000).unwrap();
+        write_gpt_header(&mut device, 0x1000, 1, 366, 2, crc32(&entries));
+        device.write(&fat12, 6 * 0x1000).unwrap();
+
+        device
+    }
+
+
+    fn write_gpt_header(device: &mut MemoryDevice, block_size: usize, lba: usize, alternate_lba: usize, entries_lba: usize, entries_crc: u32) {
+        let mut header = [0; 92];
+        header[..8].copy_from_slice(b"EFI PART");
+        header[8..12].copy_from_slice(&0x0001_0000u32.to_le_bytes());
+        header[12..16].copy_from_slice(&92u32.to_le_bytes());
+        header[24..32].copy_from_slice(&(lba as u64).to_le_bytes());
+        header[32..40].copy_from_slice(&(alternate_lba as u64).to_le_bytes());
+        header[40..48].copy_from_slice(&34u64.to_le_bytes());
+        header[48..56].copy_from_slice(&5793u64.to_le_bytes());
+        header[56..72].fill(0x5A);
+        header[72..80].copy_from_slice(&(entries_lba as u64).to_le_bytes());
+        header[80..84].copy_from_slice(&128u32.to_le_bytes());
+        header[84..88].copy_from_slice(&128u32.to_le_bytes());
+        header[88..92].copy_from_slice(&entries_crc.to_le_bytes());
+        let header_crc = crc32(&header);
+        header[16..20].copy_from_slice(&header_crc.to_le_bytes());
+
+        device.write(&header, lba * block_size).unwrap();
+    }
+
+
+    fn write_gpt_entry(entry: &mut [u8], type_guid: Guid, first_lba: u64, last_lba: u64, name: &str) {
+        entry[..16].copy_from_slice(type_guid.as_bytes());
+        entry[16..32].fill(first_lba as u8);
+        entry[32..40].copy_from_slice(&first_lba.to_le_bytes());
+        entry[40..48].copy_from_slice(&last_lba.to_le_bytes());
+        for (i, unit) in name.encode_utf16().enumerate() {
+            entry[(56 + i * 2)..(58 + i * 2)].copy_from_slice(&unit.to_le_bytes());
+        }
+    }
+
+
+    #[allow(unused)]
+    pub(crate) fn write_partition_entry(device: &mut MemoryDevice, table_lba: usize, index: usize, partition_type: u8, start_lba: u32, sector_count: u32) {
+        let mut entry = [0; 16];
+        entry[4] = partition_type;
+        entry[8..12].copy_from_slice(&start_lba.to_le_bytes());
+        entry[12..16].copy_from_slice(&sector_count.to_le_bytes());
+
+        device.write(&entry, table_lba * 0x200 + 0x1BE + index * 16).unwrap();
+    }
+
+
+    pub(crate) struct FixedTimeProvider;
+
+    impl TimeProvider for FixedTimeProvider {
+        fn now(&self) -> FatDateTime {
+            FatDateTime::new(FatDate::new(2024, 2, 29).unwrap(), FatTime::new(12, 34, 56).unwrap())
+        }
+    }
+
+
+    #[allow(unused)]
+    pub(crate) fn write_fat32_entry(device: &MemoryDevice, cluster_no: usize, value: u32) {
+        let offset = 0x4000 + cluster_no * 4;
+        device.0.borrow_mut()[offset..(offset + 4)].copy_from_slice(&value.to_le_bytes());
+    }
+
+
+    #[allow(unused)]
+    pub(crate) fn read_fat32_entry(device: &MemoryDevice, fat_index: usize, cluster_no: usize) -> u32 {
+        let offset = 0x4000 + fat_index * 1016 * 0x200 + cluster_no * 4;
+        let mut buff = [0; 4];
+        buff.copy_from_slice(&device.0.borrow()[offset..(offset + 4)]);
+        u32::from_le_bytes(buff) & 0x0FFF_FFFF
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
-    use crate::test::open_fat32_file;
+    use alloc::string::ToString;
+
+    use crate::bpb::FatType;
+    use crate::error::FatError;
+    use crate::{Fat, FatDeviceAccessible};
+    use crate::test::{fat12_memory_device, fat16_memory_device, memory_device, open_fat32_file};
 
     #[test]
     fn it_exists_hello_txt() {
@@ -132,4 +457,34 @@ mod tests {
 
         assert_eq!(&buff, &[0x68, 0x65, 0x6C, 0x6C, 0x6F, 0x0A]);
     }
+
+
+    #[test]
+    fn it_failed_remove_dir_as_file() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        assert_eq!(fat.remove_file("/TEST"), Err(FatError::IsDirectory("/TEST".to_string())));
+    }
+
+
+    #[test]
+    fn it_failed_remove_file_as_dir() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        assert_eq!(fat.remove_dir("/HELLO.TXT"), Err(FatError::NotDirectory("/HELLO.TXT".to_string())));
+    }
+
+
+    #[test]
+    fn it_mount() {
+        assert_eq!(Fat::mount(memory_device()).unwrap().fat_type(), FatType::Fat32);
+        assert_eq!(Fat::mount(fat12_memory_device()).unwrap().fat_type(), FatType::Fat12);
+        assert_eq!(Fat::mount(fat16_memory_device()).unwrap().fat_type(), FatType::Fat16);
+    }
+
+
+    #[test]
+    fn it_failed_mount_blank_device() {
+        let device = fat12_memory_device();
+        device.clone().write(&[0; 0x200], 0).unwrap();
+        assert_eq!(Fat::mount(device).err().unwrap(), FatError::InvalidBootSignature(0));
+    }
 }
@@ -0,0 +1,324 @@
+use alloc::vec::Vec;
+
+use crate::bpb::buff_read_u32;
+use crate::device::offset::OffsetDevice;
+use crate::error::{FatError, FatResult};
+use crate::FatDeviceAccessible;
+
+pub const SECTOR_SIZE: usize = 0x200;
+
+const PARTITION_TABLE_OFFSET: usize = 0x1BE;
+
+const FIRST_LOGICAL_NUMBER: usize = 5;
+
+const MAX_LOGICAL_PARTITIONS: usize = 128;
+
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Partition {
+    number: usize,
+
+    bootable: bool,
+
+    partition_type: u8,
+
+    start_lba: usize,
+
+    sector_count: usize,
+}
+
+
+impl Partition {
+    fn parse(entry: &[u8], number: usize, base_lba: usize) -> Partition {
+        Self {
+            number,
+            bootable: entry[0] == 0x80,
+            partition_type: entry[4],
+            start_lba: base_lba + buff_read_u32(entry, 8) as usize,
+            sector_count: buff_read_u32(entry, 12) as usize,
+        }
+    }
+
+
+    #[inline]
+    pub const fn number(&self) -> usize {
+        self.number
+    }
+
+
+    #[inline]
+    pub const fn is_bootable(&self) -> bool {
+        self.bootable
+    }
+
+
+    #[inline]
+    pub const fn partition_type(&self) -> u8 {
+        self.partition_type
+    }
+
+
+    #[inline]
+    pub const fn start_lba(&self) -> usize {
+        self.start_lba
+    }
+
+
+    #[inline]
+    pub const fn sector_count(&self) -> usize {
+        self.sector_count
+    }
+
+
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.start_lba * SECTOR_SIZE
+    }
+
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.sector_count * SECTOR_SIZE
+    }
+
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.partition_type == 0x00 || self.sector_count == 0
+    }
+
+
+    #[inline]
+    pub const fn is_fat(&self) -> bool {
+        matches!(self.partition_type, 0x01 | 0x04 | 0x06 | 0x0B | 0x0C | 0x0E)
+    }
+
+
+    #[inline]
+    pub const fn is_extended(&self) -> bool {
+        matches!(self.partition_type, 0x05 | 0x0F | 0x85)
+    }
+}
+
+
+pub fn partitions<D>(device: &D) -> FatResult<Vec<Partition>>
+    where D: FatDeviceAccessible
+{
+    let primaries = read_table(device, 0)?;
+    let mut partitions = primaries
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| Partition::parse(entry, i + 1, 0))
+        .filter(|partition| !partition.is_empty())
+        .collect::<Vec<_>>();
+    if partitions.iter().any(|partition| partition.start_lba() == 0) {
+        return Err(FatError::InvalidPartitionTable);
+    }
+
+    let extended = partitions
+        .iter()
+        .find(|partition| partition.is_extended())
+        .map(|partition| (partition.start_lba(), partition.start_lba() + partition.sector_count()));
+
+    if let Some((extended_lba, extended_end)) = extended {
+        let mut ebr_lba = extended_lba;
+        for number in FIRST_LOGICAL_NUMBER..(FIRST_LOGICAL_NUMBER + MAX_LOGICAL_PARTITIONS) {
+            let table = read_table(device, ebr_lba)?;
+            let logical = Partition::parse(&table[0], number, ebr_lba);
+            if !logical.is_empty() {
+                if !is_inside(&logical, ebr_lba, extended_end) {
+                    return Err(FatError::InvalidPartitionTable);
+                }
+                partitions.push(logical);
+            }
+
+            let next = Partition::parse(&table[1], 0, extended_lba);
+            if next.is_empty() || !next.is_extended() {
+                return Ok(partitions);
+            }
+            if !is_inside(&next, extended_lba, extended_end) {
+                return Err(FatError::InvalidPartitionTable);
+            }
+            ebr_lba = next.start_lba();
+        }
+
+        return Err(FatError::InvalidPartitionTable);
+    }
+
+    Ok(partitions)
+}
+
+
+pub fn partition<D>(device: D, number: usize) -> FatResult<OffsetDevice<D>>
+    where D: FatDeviceAccessible
+{
+    let partition = partitions(&device)?
+        .into_iter()
+        .find(|partition| partition.number() == number)
+        .ok_or(FatError::NotfoundPartition(number))?;
+
+    if !partition.is_fat() {
+        return Err(FatError::NotFatPartition(partition.partition_type()));
+    }
+
+    Ok(OffsetDevice::new(device, partition.offset(), partition.len()))
+}
+
+
+fn read_table<D>(device: &D, lba: usize) -> FatResult<[[u8; 16]; 4]>
+    where D: FatDeviceAccessible
+{
+    let mut sector = [0; SECTOR_SIZE];
+    device.read(&mut sector, lba * SECTOR_SIZE, SECTOR_SIZE)?;
+
+    let signature = u16::from_le_bytes([sector[510], sector[511]]);
+    if signature != 0xAA55 {
+        return Err(FatError::InvalidBootSignature(signature));
+    }
+    if is_fat_vbr(&sector) {
+        return Err(FatError::InvalidPartitionTable);
+    }
+
+    let mut table = [[0; 16]; 4];
+    for (i, entry) in table.iter_mut().enumerate() {
+        let offset = PARTITION_TABLE_OFFSET + i * 16;
+        entry.copy_from_slice(&sector[offset..(offset + 16)]);
+    }
+
+    if table.iter().any(|entry| !matches!(entry[0], 0x00 | 0x80)) {
+        return Err(FatError::InvalidPartitionTable);
+    }
+
+    Ok(table)
+}
+
+
+/// A superfloppy starts with a FAT volume boot record instead of an MBR.
+fn is_fat_vbr(sector: &[u8; SECTOR_SIZE]) -> bool {
+    let bytes_per_sector = u16::from_le_bytes([sector[11], sector[12]]);
+    let reserved_sectors = u16::from_le_bytes([sector[14], sector[15]]);
+
+    matches!(sector[0], 0xEB | 0xE9)
+        && matches!(bytes_per_sector, 512 | 1024 | 2048 | 4096)
+        && sector[13].is_power_of_two()
+        && 0 < reserved_sectors
+        && 0 < sector[16]
+        && (sector[21] == 0xF0 || 0xF8 <= sector[21])
+}
+
+
+/// Whether the partition starts after the table at `table_lba` and ends within `end_lba`.
+fn is_inside(partition: &Partition, table_lba: usize, end_lba: usize) -> bool {
+    table_lba < partition.start_lba() && partition.start_lba() + partition.sector_count() <= end_lba
+}
+
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{Fat, FatDeviceAccessible};
+    use crate::bpb::FatType;
+    use crate::error::FatError;
+    use crate::mbr::{partition, partitions, SECTOR_SIZE};
+    use crate::test::{fat12_memory_device, partitioned_memory_device, write_partition_entry};
+
+    #[test]
+    fn it_parse_primary_and_logical_partitions() {
+        let partitions = partitions(&partitioned_memory_device())
+            .unwrap()
+            .into_iter()
+            .map(|partition| (partition.number(), partition.partition_type(), partition.start_lba(), partition.sector_count()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(partitions, [
+            (1, 0x01, 1, 2880),
+            (2, 0x83, 2881, 8),
+            (3, 0x0F, 2889, 5762),
+            (5, 0x01, 2890, 2880),
+            (6, 0x0E, 5771, 2880),
+        ]);
+    }
+
+
+    #[test]
+    fn it_mount_primary_partition() {
+        let device = partitioned_memory_device();
+        let fat = Fat::mount(partition(device.clone(), 1).unwrap()).unwrap();
+        fat.create_file("/HELLO.TXT").unwrap().append(b"hello").unwrap();
+
+        assert_eq!(fat.fat_type(), FatType::Fat12);
+        assert_eq!(fat.open_file("/HELLO.TXT").unwrap().read_boxed().unwrap(), b"hello");
+
+        let mut buff = [0; 5];
+        device.read(&mut buff, (1 + 33) * SECTOR_SIZE, 5).unwrap();
+        assert_eq!(&buff, b"hello");
+    }
+
+
+    #[test]
+    fn it_mount_logical_partitions() {
+        let device = partitioned_memory_device();
+        let fifth = Fat::mount(partition(device.clone(), 5).unwrap()).unwrap();
+        fifth.create_file("/FIFTH.TXT").unwrap();
+        let sixth = Fat::mount(partition(device.clone(), 6).unwrap()).unwrap();
+        sixth.create_file("/SIXTH.TXT").unwrap();
+
+        assert!(fifth.open("/FIFTH.TXT").is_ok());
+        assert!(fifth.open("/SIXTH.TXT").is_err());
+        assert!(sixth.open("/SIXTH.TXT").is_ok());
+        assert!(sixth.open("/FIFTH.TXT").is_err());
+    }
+
+
+    #[test]
+    fn it_failed_open_non_fat_partition() {
+        let device = partitioned_memory_device();
+
+        assert_eq!(partition(device.clone(), 2).err().unwrap(), FatError::NotFatPartition(0x83));
+        assert_eq!(partition(device.clone(), 3).err().unwrap(), FatError::NotFatPartition(0x0F));
+        assert_eq!(partition(device, 4).err().unwrap(), FatError::NotfoundPartition(4));
+    }
+
+
+    #[test]
+    fn it_failed_parse_looping_logical_chain() {
+        let mut device = partitioned_memory_device();
+        write_partition_entry(&mut device, 5770, 1, 0x05, 2881, 2881);
+
+        assert_eq!(partitions(&device), Err(FatError::InvalidPartitionTable));
+    }
+
+
+    #[test]
+    fn it_failed_parse_without_signature() {
+        let mut device = fat12_memory_device();
+        device.write(&[0; 2], 510).unwrap();
+
+        assert_eq!(partitions(&device), Err(FatError::InvalidBootSignature(0)));
+    }
+
+
+    #[test]
+    fn it_failed_parse_superfloppy() {
+        assert_eq!(partitions(&fat12_memory_device()), Err(FatError::InvalidPartitionTable));
+    }
+
+
+    #[test]
+    fn it_failed_parse_invalid_boot_indicator() {
+        let mut device = partitioned_memory_device();
+        device.write(&[0x01], 0x1BE).unwrap();
+
+        assert_eq!(partitions(&device), Err(FatError::InvalidPartitionTable));
+    }
+
+
+    #[test]
+    fn it_failed_parse_logical_partition_past_extended() {
+        let mut device = partitioned_memory_device();
+        write_partition_entry(&mut device, 5770, 0, 0x0E, 1, 2881);
+
+        assert_eq!(partitions(&device), Err(FatError::InvalidPartitionTable));
+    }
+}
@@ -0,0 +1,72 @@
+use alloc::rc::Rc;
+use core::fmt::{Debug, Formatter};
+
+use crate::time::{EpochTimeProvider, FatDateTime, TimeProvider};
+
+#[derive(Clone)]
+pub struct MountOptions {
+    time_provider: Rc<dyn TimeProvider>,
+
+    update_access_date: bool,
+}
+
+
+impl MountOptions {
+    #[inline]
+    pub fn new() -> MountOptions {
+        Self {
+            time_provider: Rc::new(EpochTimeProvider),
+            update_access_date: true,
+        }
+    }
+
+
+    #[inline]
+    pub fn with_time_provider<T>(self, time_provider: T) -> MountOptions
+        where T: TimeProvider + 'static
+    {
+        Self {
+            time_provider: Rc::new(time_provider),
+            ..self
+        }
+    }
+
+
+    #[inline]
+    pub fn with_update_access_date(self, update_access_date: bool) -> MountOptions {
+        Self {
+            update_access_date,
+            ..self
+        }
+    }
+
+
+    #[inline]
+    pub fn now(&self) -> FatDateTime {
+        self.time_provider.now()
+    }
+
+
+    #[inline]
+    pub const fn update_access_date(&self) -> bool {
+        self.update_access_date
+    }
+}
+
+
+impl Default for MountOptions {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+impl Debug for MountOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f
+            .debug_struct("MountOptions")
+            .field("update_access_date", &self.update_access_date)
+            .finish()
+    }
+}
@@ -0,0 +1,456 @@
+use alloc::vec::Vec;
+
+use crate::bpb::{BpbReadable, FatType};
+use crate::bpb::fs_info::FsInfo;
+use crate::error::{FatError, FatResult};
+use crate::FatDeviceAccessible;
+use crate::table::chain::ClusterChain;
+use crate::table::stats::VolumeStats;
+
+pub mod chain;
+pub mod stats;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum FatEntry {
+    Free,
+    Next(usize),
+    Bad,
+    EndOfChain,
+    Reserved(u32),
+}
+
+
+impl FatEntry {
+    pub const fn from_fat12(raw: u16) -> FatEntry {
+        match raw & 0x0FFF {
+            0x000 => Self::Free,
+            0xFF7 => Self::Bad,
+            0xFF8..=0xFFF => Self::EndOfChain,
+            reserved @ (0x001 | 0xFF0..=0xFF6) => Self::Reserved(reserved as u32),
+            next => Self::Next(next as usize)
+        }
+    }
+
+
+    pub const fn from_fat16(raw: u16) -> FatEntry {
+        match raw {
+            0x0000 => Self::Free,
+            0xFFF7 => Self::Bad,
+            0xFFF8..=0xFFFF => Self::EndOfChain,
+            reserved @ (0x0001 | 0xFFF0..=0xFFF6) => Self::Reserved(reserved as u32),
+            next => Self::Next(next as usize)
+        }
+    }
+
+
+    pub const fn from_fat32(raw: u32) -> FatEntry {
+        match raw & 0x0FFF_FFFF {
+            0x00 => Self::Free,
+            0x0FFF_FFF7 => Self::Bad,
+            0x0FFF_FFF8..=0x0FFF_FFFF => Self::EndOfChain,
+            reserved @ (0x01 | 0x0FFF_FFF0..=0x0FFF_FFF6) => Self::Reserved(reserved),
+            next => Self::Next(next as usize)
+        }
+    }
+
+
+    pub const fn into_fat32(self) -> u32 {
+        match self {
+            Self::Free => 0x00,
+            Self::Next(next) => next as u32,
+            Self::Bad => 0x0FFF_FFF7,
+            Self::EndOfChain => 0x0FFF_FFFF,
+            Self::Reserved(raw) => raw,
+        }
+    }
+
+
+    pub const fn into_fat16(self) -> u16 {
+        match self {
+            Self::Free => 0x0000,
+            Self::Next(next) => next as u16,
+            Self::Bad => 0xFFF7,
+            Self::EndOfChain => 0xFFFF,
+            Self::Reserved(raw) => raw as u16,
+        }
+    }
+
+
+    pub const fn into_fat12(self) -> u16 {
+        match self {
+            Self::EndOfChain => 0x0FFF,
+            Self::Bad => 0x0FF7,
+            entry => entry.into_fat16() & 0x0FFF
+        }
+    }
+}
+
+
+#[derive(Clone)]
+pub struct FatTable<D> {
+    bpb: D,
+}
+
+
+impl<D> FatTable<D>
+    where D: FatDeviceAccessible + BpbReadable + Clone
+{
+    #[inline]
+    pub const fn new(bpb: D) -> FatTable<D> {
+        Self {
+            bpb
+        }
+    }
+
+
+    pub fn entry_at(&self, cluster_no: usize) -> FatResult<FatEntry> {
+        let offset = self.bpb.fat_entry_offset_at(cluster_no)?;
+
+        match self.bpb.fat_type()? {
+            FatType::Fat12 if cluster_no & 1 == 0 => Ok(FatEntry::from_fat12(self.bpb.read_u16(offset)?)),
+            FatType::Fat12 => Ok(FatEntry::from_fat12(self.bpb.read_u16(offset)? >> 4)),
+            FatType::Fat16 => Ok(FatEntry::from_fat16(self.bpb.read_u16(offset)?)),
+            FatType::Fat32 => Ok(FatEntry::from_fat32(self.bpb.read_u32(offset)?)),
+        }
+    }
+
+
+    #[inline]
+    pub fn chain(&self, first_cluster_no: usize) -> ClusterChain<D> {
+        ClusterChain::new(self.clone(), first_cluster_no)
+    }
+
+
+    pub fn set_entry_at(&mut self, cluster_no: usize, entry: FatEntry) -> FatResult {
+        let offset = self.bpb.fat_entry_offset_at(cluster_no)?;
+        let fat_bytes = self.bpb.fat_bytes()?;
+        let fat_type = self.bpb.fat_type()?;
+
+        for fat_index in 0..self.bpb.fat_count()? {
+            let offset = offset + fat_index * fat_bytes;
+
+            match fat_type {
+                FatType::Fat12 => {
+                    let raw = self.bpb.read_u16(offset)?;
+                    let raw = if cluster_no & 1 == 0 {
+                        (raw & 0xF000) | entry.into_fat12()
+                    } else {
+                        (raw & 0x000F) | (entry.into_fat12() << 4)
+                    };
+                    self.bpb.write_u16(offset, raw)?;
+                }
+                FatType::Fat16 => self.bpb.write_u16(offset, entry.into_fat16())?,
+                FatType::Fat32 => {
+                    let raw = (self.bpb.read_u32(offset)? & 0xF000_0000) | entry.into_fat32();
+                    self.bpb.write_u32(offset, raw)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+
+    pub fn allocate(&mut self) -> FatResult<usize> {
+        let cluster_count = self.bpb.cluster_count()?;
+        let mut fs_info = self.valid_fs_info()?;
+        let start = match &fs_info {
+            Some(fs_info) => Some(fs_info.next_free()? as usize)
+                .filter(|hint| (2..(cluster_count + 2)).contains(hint))
+                .unwrap_or(2),
+            None => 2
+        };
+
+        for i in 0..cluster_count {
+            let cluster_no = 2 + (start - 2 + i) % cluster_count;
+            if self.entry_at(cluster_no)? == FatEntry::Free {
+                self.set_entry_at(cluster_no, FatEntry::EndOfChain)?;
+                if let Some(fs_info) = &mut fs_info {
+                    fs_info.adjust_free_count(-1)?;
+                    fs_info.write_next_free((2 + (cluster_no - 1) % cluster_count) as u32)?;
+                }
+                return Ok(cluster_no);
+            }
+        }
+
+        Err(FatError::NoFreeCluster)
+    }
+
+
+    pub fn append_cluster(&mut self, last_cluster_no: Option<usize>) -> FatResult<usize> {
+        let cluster_no = self.allocate()?;
+        if let Some(last_cluster_no) = last_cluster_no {
+            self.set_entry_at(last_cluster_no, FatEntry::Next(cluster_no))?;
+        }
+
+        Ok(cluster_no)
+    }
+
+
+    pub fn free_chain(&mut self, first_cluster_no: usize) -> FatResult {
+        let clusters = self
+            .chain(first_cluster_no)
+            .collect::<FatResult<Vec<usize>>>()?;
+
+        self.free_clusters(&clusters)
+    }
+
+
+    pub fn free_clusters(&mut self, clusters: &[usize]) -> FatResult {
+        for cluster_no in clusters {
+            self.set_entry_at(*cluster_no, FatEntry::Free)?;
+        }
+
+        let mut fs_info = match self.valid_fs_info()? {
+            Some(fs_info) => fs_info,
+            None => return Ok(())
+        };
+
+        fs_info.adjust_free_count(clusters.len() as isize)?;
+        match clusters.iter().min() {
+            Some(lowest) if *lowest < fs_info.next_free_hint()?.unwrap_or(usize::MAX) => {
+                fs_info.write_next_free(*lowest as u32)
+            }
+            _ => Ok(())
+        }
+    }
+
+
+    pub fn stats(&self) -> FatResult<VolumeStats> {
+        let cluster_count = self.bpb.cluster_count()?;
+        let free_count = match self.valid_fs_info()? {
+            Some(fs_info) => fs_info.known_free_count()?,
+            None => None
+        };
+
+        match free_count {
+            Some(free_count) if free_count <= cluster_count => {
+                Ok(VolumeStats::new(self.bpb.bytes_per_cluster()?, cluster_count, free_count, None))
+            }
+            _ => self.scan_stats()
+        }
+    }
+
+
+    pub fn scan_stats(&self) -> FatResult<VolumeStats> {
+        let mut free_count = 0;
+        let mut bad_count = 0;
+        for cluster_no in 2..(self.bpb.cluster_count()? + 2) {
+            match self.entry_at(cluster_no)? {
+                FatEntry::Free => free_count += 1,
+                FatEntry::Bad => bad_count += 1,
+                _ => {}
+            }
+        }
+
+        Ok(VolumeStats::new(self.bpb.bytes_per_cluster()?, self.bpb.cluster_count()?, free_count, Some(bad_count)))
+    }
+
+
+    /// Checks the FSInfo signatures once, so the caller can use the unchecked accessors.
+    fn valid_fs_info(&self) -> FatResult<Option<FsInfo<D>>> {
+        match self.fs_info()? {
+            Some(fs_info) if fs_info.is_valid()? => Ok(Some(fs_info)),
+            _ => Ok(None)
+        }
+    }
+
+
+    fn fs_info(&self) -> FatResult<Option<FsInfo<D>>> {
+        Ok(self
+            .bpb
+            .fs_info_offset()?
+            .map(|offset| FsInfo::new(self.bpb.clone(), offset)))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::bpb::{Bpb, BpbReadable};
+    use crate::bpb::fs_info::{FsInfo, UNKNOWN_COUNT, UNKNOWN_NEXT_FREE};
+    use crate::table::{FatEntry, FatTable};
+    use crate::{Fat, FatDeviceAccessible};
+    use crate::test::{fat12_memory_device, fat16_memory_device, file_device, memory_device, read_fat32_entry};
+
+    #[test]
+    fn it_decode_fat32_entries() {
+        assert_eq!(FatEntry::from_fat32(0x0000_0000), FatEntry::Free);
+        assert_eq!(FatEntry::from_fat32(0x0000_0003), FatEntry::Next(3));
+        assert_eq!(FatEntry::from_fat32(0x0FFF_FFF7), FatEntry::Bad);
+        assert_eq!(FatEntry::from_fat32(0x0FFF_FFF8), FatEntry::EndOfChain);
+        assert_eq!(FatEntry::from_fat32(0xFFFF_FFFF), FatEntry::EndOfChain);
+        assert_eq!(FatEntry::from_fat32(0x0FFF_FFF0), FatEntry::Reserved(0x0FFF_FFF0));
+    }
+
+
+    #[test]
+    fn it_hello_txt_entry_is_end_of_chain() {
+        let table = FatTable::new(Bpb::new(file_device()).unwrap());
+        assert_eq!(table.entry_at(3), Ok(FatEntry::EndOfChain));
+    }
+
+
+    #[test]
+    fn it_set_entry_into_every_fat() {
+        let device = memory_device();
+        let mut table = FatTable::new(Bpb::new(device.clone()).unwrap());
+        table.set_entry_at(0x10, FatEntry::Next(0x11)).unwrap();
+
+        assert_eq!(read_fat32_entry(&device, 0, 0x10), 0x11);
+        assert_eq!(read_fat32_entry(&device, 1, 0x10), 0x11);
+    }
+
+
+    #[test]
+    fn it_allocate_from_next_free_hint() {
+        let device = memory_device();
+        let mut table = FatTable::new(Bpb::new(device.clone()).unwrap());
+
+        assert_eq!(table.allocate(), Ok(6));
+        assert_eq!(table.entry_at(6), Ok(FatEntry::EndOfChain));
+        assert_eq!(table.append_cluster(Some(6)), Ok(7));
+        assert_eq!(table.entry_at(6), Ok(FatEntry::Next(7)));
+        assert_eq!(read_fat32_entry(&device, 1, 6), 7);
+        assert_eq!(FsInfo::new(device.clone(), 0x200).free_count(), Ok(130035));
+        assert_eq!(FsInfo::new(device, 0x200).next_free(), Ok(8));
+    }
+
+
+    #[test]
+    fn it_allocate_first_free_cluster_without_hint() {
+        let device = memory_device();
+        FsInfo::new(device.clone(), 0x200).write_next_free(UNKNOWN_NEXT_FREE).unwrap();
+        let mut table = FatTable::new(Bpb::new(device.clone()).unwrap());
+
+        assert_eq!(table.allocate(), Ok(4));
+        assert_eq!(FsInfo::new(device, 0x200).next_free(), Ok(5));
+    }
+
+
+    #[test]
+    fn it_wrap_around_from_last_cluster() {
+        let device = memory_device();
+        let mut table = FatTable::new(Bpb::new(device.clone()).unwrap());
+        let last = table.bpb.cluster_count().unwrap() + 1;
+        FsInfo::new(device.clone(), 0x200).write_next_free(last as u32).unwrap();
+        table.set_entry_at(last, FatEntry::Bad).unwrap();
+
+        assert_eq!(table.allocate(), Ok(4));
+    }
+
+
+    #[test]
+    fn it_free_chain() {
+        let device = memory_device();
+        let mut table = FatTable::new(Bpb::new(device.clone()).unwrap());
+        let first = table.allocate().unwrap();
+        let second = table.append_cluster(Some(first)).unwrap();
+        table.free_chain(first).unwrap();
+
+        assert_eq!(table.entry_at(first), Ok(FatEntry::Free));
+        assert_eq!(table.entry_at(second), Ok(FatEntry::Free));
+        assert_eq!(FsInfo::new(device.clone(), 0x200).free_count(), Ok(130037));
+        assert_eq!(FsInfo::new(device, 0x200).next_free(), Ok(first as u32));
+    }
+
+
+    #[test]
+    fn it_stats_from_fs_info() {
+        let device = memory_device();
+        let table = FatTable::new(Bpb::new(device.clone()).unwrap());
+        let stats = table.stats().unwrap();
+
+        assert_eq!(stats.bytes_per_cluster(), 0x400);
+        assert_eq!(stats.free_clusters(), 130037);
+        assert_eq!(stats.bad_clusters(), None);
+        assert_eq!(stats.free_bytes(), 130037 * 0x400);
+        assert_eq!(stats.used_bytes() + stats.free_bytes(), stats.total_bytes());
+    }
+
+
+    #[test]
+    fn it_stats_scan_fat_if_free_count_is_unknown() {
+        let device = memory_device();
+        FsInfo::new(device.clone(), 0x200).write_free_count(UNKNOWN_COUNT).unwrap();
+        let mut table = FatTable::new(Bpb::new(device.clone()).unwrap());
+        table.set_entry_at(0x10, FatEntry::Bad).unwrap();
+        let stats = table.stats().unwrap();
+
+        assert_eq!(stats, table.scan_stats().unwrap());
+        assert_eq!(stats.bad_clusters(), Some(1));
+        assert_eq!(stats.free_clusters(), 130036);
+    }
+
+
+    #[test]
+    fn it_stats_on_fat12() {
+        let mut table = FatTable::new(Bpb::new(fat12_memory_device()).unwrap());
+        table.allocate().unwrap();
+        table.set_entry_at(0x20, FatEntry::Bad).unwrap();
+        let stats = table.stats().unwrap();
+
+        assert_eq!(stats.total_clusters(), 2847);
+        assert_eq!(stats.free_clusters(), 2845);
+        assert_eq!(stats.bad_clusters(), Some(1));
+        assert_eq!(stats.used_bytes(), 2 * 0x200);
+    }
+
+
+    #[test]
+    fn it_decode_fat12_and_fat16_entries() {
+        assert_eq!(FatEntry::from_fat12(0x0FF7), FatEntry::Bad);
+        assert_eq!(FatEntry::from_fat12(0xFFF8), FatEntry::EndOfChain);
+        assert_eq!(FatEntry::from_fat12(0x0ABC), FatEntry::Next(0xABC));
+        assert_eq!(FatEntry::from_fat16(0xFFF7), FatEntry::Bad);
+        assert_eq!(FatEntry::from_fat16(0xFFFF), FatEntry::EndOfChain);
+        assert_eq!(FatEntry::from_fat16(0x1234), FatEntry::Next(0x1234));
+    }
+
+
+    #[test]
+    fn it_pack_fat12_entries() {
+        let device = fat12_memory_device();
+        let mut table = FatTable::new(Bpb::new(device.clone()).unwrap());
+        table.set_entry_at(2, FatEntry::Next(3)).unwrap();
+        table.set_entry_at(3, FatEntry::EndOfChain).unwrap();
+
+        let mut buff = [0; 3];
+        device.read(&mut buff, 0x203, 3).unwrap();
+        assert_eq!(buff, [0x03, 0xF0, 0xFF]);
+        device.read(&mut buff, 0x203 + 9 * 0x200, 3).unwrap();
+        assert_eq!(buff, [0x03, 0xF0, 0xFF]);
+
+        assert_eq!(table.entry_at(1), Ok(FatEntry::EndOfChain));
+        assert_eq!(table.entry_at(2), Ok(FatEntry::Next(3)));
+        assert_eq!(table.entry_at(3), Ok(FatEntry::EndOfChain));
+        assert_eq!(table.entry_at(4), Ok(FatEntry::Free));
+    }
+
+
+    #[test]
+    fn it_allocate_on_fat16() {
+        let mut table = FatTable::new(Bpb::new(fat16_memory_device()).unwrap());
+
+        assert_eq!(table.allocate(), Ok(2));
+        assert_eq!(table.append_cluster(Some(2)), Ok(3));
+        assert_eq!(table.entry_at(2), Ok(FatEntry::Next(3)));
+        assert_eq!(table.entry_at(3), Ok(FatEntry::EndOfChain));
+    }
+
+
+    #[test]
+    fn it_stats_follow_writes() {
+        let fat = Fat::mount(memory_device()).unwrap();
+        fat.create_file("/WORLD.TXT").unwrap().append(&[0; 0x500]).unwrap();
+        assert_eq!(fat.stats().unwrap().free_clusters(), 130035);
+
+        fat.remove_file("/WORLD.TXT").unwrap();
+        assert_eq!(fat.stats().unwrap().free_clusters(), 130037);
+
+        let fat16 = Fat::mount(fat16_memory_device()).unwrap();
+        let before = fat16.stats().unwrap();
+        fat16.create_file("/A.TXT").unwrap().append(b"hello").unwrap();
+        assert_eq!(fat16.stats().unwrap().free_bytes(), before.free_bytes() - 0x800);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::bpb::BpbReadable;
+use crate::error::{FatError, FatResult};
+use crate::FatDeviceAccessible;
+use crate::table::{FatEntry, FatTable};
+
+#[derive(Clone)]
+pub struct ClusterChain<D> {
+    table: FatTable<D>,
+    next: Option<usize>,
+    steps: usize,
+}
+
+
+impl<D> ClusterChain<D>
+    where D: FatDeviceAccessible + BpbReadable + Clone
+{
+    #[inline]
+    pub const fn new(table: FatTable<D>, first_cluster_no: usize) -> ClusterChain<D> {
+        Self {
+            table,
+            next: if first_cluster_no < 2 { None } else { Some(first_cluster_no) },
+            steps: 0,
+        }
+    }
+
+
+    fn follow(&mut self, cluster_no: usize) -> FatResult<Option<usize>> {
+        let cluster_count = self.table.bpb.cluster_count()?;
+        if cluster_count + 2 <= cluster_no {
+            return Err(FatError::InvalidFatEntry(cluster_no as u32));
+        }
+
+        self.steps += 1;
+        if cluster_count < self.steps {
+            return Err(FatError::CyclicClusterChain(cluster_no));
+        }
+
+        match self.table.entry_at(cluster_no)? {
+            FatEntry::Next(next) if cluster_count + 2 <= next => Err(FatError::InvalidFatEntry(next as u32)),
+            FatEntry::Next(next) => Ok(Some(next)),
+            FatEntry::EndOfChain => Ok(None),
+            FatEntry::Bad => Err(FatError::BadClusterInChain(cluster_no)),
+            FatEntry::Free => Err(FatError::FreeClusterInChain(cluster_no)),
+            FatEntry::Reserved(raw) => Err(FatError::InvalidFatEntry(raw)),
+        }
+    }
+}
+
+
+impl<D> Iterator for ClusterChain<D>
+    where D: FatDeviceAccessible + BpbReadable + Clone
+{
+    type Item = FatResult<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cluster_no = self.next.take()?;
+
+        match self.follow(cluster_no) {
+            Ok(next) => {
+                self.next = next;
+                Some(Ok(cluster_no))
+            }
+            Err(e) => Some(Err(e))
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use crate::bpb::Bpb;
+    use crate::error::{FatError, FatResult};
+    use crate::table::FatTable;
+    use crate::test::{file_device, memory_device, write_fat32_entry};
+
+    #[test]
+    fn it_hello_txt_chain() {
+        let table = FatTable::new(Bpb::new(file_device()).unwrap());
+        let chain = table
+            .chain(3)
+            .collect::<FatResult<Vec<usize>>>();
+
+        assert_eq!(chain, Ok(vec![3]));
+    }
+
+
+    #[test]
+    fn it_chain_is_empty_if_first_cluster_is_zero() {
+        let table = FatTable::new(Bpb::new(file_device()).unwrap());
+        assert_eq!(table.chain(0).count(), 0);
+    }
+
+
+    #[test]
+    fn it_follow_fragmented_chain() {
+        let device = memory_device();
+        write_fat32_entry(&device, 3, 0x10);
+        write_fat32_entry(&device, 0x10, 0x08);
+        write_fat32_entry(&device, 0x08, 0x0FFF_FFFF);
+
+        let chain = FatTable::new(Bpb::new(device).unwrap())
+            .chain(3)
+            .collect::<FatResult<Vec<usize>>>();
+
+        assert_eq!(chain, Ok(vec![3, 0x10, 0x08]));
+    }
+
+
+    #[test]
+    fn it_bad_cluster_in_chain() {
+        let device = memory_device();
+        write_fat32_entry(&device, 3, 0x10);
+        write_fat32_entry(&device, 0x10, 0x0FFF_FFF7);
+
+        let chain = FatTable::new(Bpb::new(device).unwrap())
+            .chain(3)
+            .collect::<FatResult<Vec<usize>>>();
+
+        assert_eq!(chain, Err(FatError::BadClusterInChain(0x10)));
+    }
+
+
+    #[test]
+    fn it_free_cluster_in_chain() {
+        let device = memory_device();
+        write_fat32_entry(&device, 3, 0x10);
+
+        let chain = FatTable::new(Bpb::new(device).unwrap())
+            .chain(3)
+            .collect::<FatResult<Vec<usize>>>();
+
+        assert_eq!(chain, Err(FatError::FreeClusterInChain(0x10)));
+    }
+
+
+    #[test]
+    fn it_failed_follow_self_loop() {
+        let device = memory_device();
+        write_fat32_entry(&device, 3, 0x10);
+        write_fat32_entry(&device, 0x10, 0x10);
+
+        let chain = FatTable::new(Bpb::new(device).unwrap())
+            .chain(3)
+            .collect::<FatResult<Vec<usize>>>();
+
+        assert_eq!(chain, Err(FatError::CyclicClusterChain(0x10)));
+    }
+
+
+    #[test]
+    fn it_failed_follow_out_of_range_next() {
+        let device = memory_device();
+        write_fat32_entry(&device, 3, 0x0FFF_FF00);
+
+        let mut chain = FatTable::new(Bpb::new(device).unwrap()).chain(3);
+
+        assert_eq!(chain.next(), Some(Err(FatError::InvalidFatEntry(0x0FFF_FF00))));
+        assert_eq!(chain.next(), None);
+        assert_eq!(
+            FatTable::new(Bpb::new(memory_device()).unwrap()).chain(0x0FFF_FF00).next(),
+            Some(Err(FatError::InvalidFatEntry(0x0FFF_FF00)))
+        );
+    }
+}
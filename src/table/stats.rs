@@ -0,0 +1,66 @@
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct VolumeStats {
+    bytes_per_cluster: usize,
+
+    total_clusters: usize,
+
+    free_clusters: usize,
+
+    bad_clusters: Option<usize>,
+}
+
+
+impl VolumeStats {
+    #[inline]
+    pub const fn new(bytes_per_cluster: usize, total_clusters: usize, free_clusters: usize, bad_clusters: Option<usize>) -> VolumeStats {
+        Self {
+            bytes_per_cluster,
+            total_clusters,
+            free_clusters,
+            bad_clusters,
+        }
+    }
+
+
+    #[inline]
+    pub const fn bytes_per_cluster(&self) -> usize {
+        self.bytes_per_cluster
+    }
+
+
+    #[inline]
+    pub const fn total_clusters(&self) -> usize {
+        self.total_clusters
+    }
+
+
+    #[inline]
+    pub const fn free_clusters(&self) -> usize {
+        self.free_clusters
+    }
+
+
+    /// `None` when the free count was taken from FSInfo and the FAT was not scanned.
+    #[inline]
+    pub const fn bad_clusters(&self) -> Option<usize> {
+        self.bad_clusters
+    }
+
+
+    #[inline]
+    pub const fn total_bytes(&self) -> usize {
+        self.total_clusters * self.bytes_per_cluster
+    }
+
+
+    #[inline]
+    pub const fn free_bytes(&self) -> usize {
+        self.free_clusters * self.bytes_per_cluster
+    }
+
+
+    #[inline]
+    pub const fn used_bytes(&self) -> usize {
+        (self.total_clusters - self.free_clusters) * self.bytes_per_cluster
+    }
+}
@@ -0,0 +1,442 @@
+use core::fmt::{Debug, Formatter};
+
+use crate::error::{FatError, FatResult};
+
+pub const FAT_EPOCH_YEAR: u16 = 1980;
+
+const MAX_YEAR: u16 = FAT_EPOCH_YEAR + 127;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+const DAYS_FROM_UNIX_EPOCH_TO_FAT_EPOCH: u64 = 3652;
+
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct FatDate(u16);
+
+
+impl FatDate {
+    pub const EPOCH: FatDate = FatDate((1 << 5) | 1);
+
+
+    pub fn new(year: u16, month: u8, day: u8) -> FatResult<FatDate> {
+        if !is_valid_date(year, month, day) {
+            return Err(FatError::InvalidDate(year, month, day));
+        }
+
+        Ok(Self(((year - FAT_EPOCH_YEAR) << 9) | ((month as u16) << 5) | day as u16))
+    }
+
+
+    #[inline]
+    pub const fn from_raw(raw: u16) -> FatDate {
+        Self(raw)
+    }
+
+
+    #[inline]
+    pub const fn raw(&self) -> u16 {
+        self.0
+    }
+
+
+    #[inline]
+    pub const fn year(&self) -> u16 {
+        FAT_EPOCH_YEAR + (self.0 >> 9)
+    }
+
+
+    #[inline]
+    pub const fn month(&self) -> u8 {
+        ((self.0 >> 5) & 0x0F) as u8
+    }
+
+
+    #[inline]
+    pub const fn day(&self) -> u8 {
+        (self.0 & 0x1F) as u8
+    }
+
+
+    #[inline]
+    pub const fn is_valid(&self) -> bool {
+        is_valid_date(self.year(), self.month(), self.day())
+    }
+
+
+    pub fn days_since_unix_epoch(&self) -> FatResult<u64> {
+        if !self.is_valid() {
+            return Err(FatError::InvalidDate(self.year(), self.month(), self.day()));
+        }
+
+        let mut days = DAYS_FROM_UNIX_EPOCH_TO_FAT_EPOCH;
+        let mut year = FAT_EPOCH_YEAR;
+        while year < self.year() {
+            days += if is_leap_year(year) { 366 } else { 365 };
+            year += 1;
+        }
+
+        let mut month = 1;
+        while month < self.month() {
+            days += days_in_month(year, month) as u64;
+            month += 1;
+        }
+
+        Ok(days + self.day() as u64 - 1)
+    }
+
+
+    pub fn from_days_since_unix_epoch(unix_days: u64) -> FatResult<FatDate> {
+        let out_of_range = FatError::TimestampOutOfRange(unix_days.saturating_mul(SECONDS_PER_DAY));
+        if unix_days < DAYS_FROM_UNIX_EPOCH_TO_FAT_EPOCH {
+            return Err(out_of_range);
+        }
+
+        let mut days = unix_days - DAYS_FROM_UNIX_EPOCH_TO_FAT_EPOCH;
+        let mut year = FAT_EPOCH_YEAR;
+        loop {
+            let days_in_year = if is_leap_year(year) { 366 } else { 365 };
+            if days < days_in_year || MAX_YEAR < year {
+                break;
+            }
+            days -= days_in_year;
+            year += 1;
+        }
+        if MAX_YEAR < year {
+            return Err(out_of_range);
+        }
+
+        let mut month = 1;
+        while days_in_month(year, month) as u64 <= days {
+            days -= days_in_month(year, month) as u64;
+            month += 1;
+        }
+
+        FatDate::new(year, month, days as u8 + 1)
+    }
+}
+
+
+impl Debug for FatDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year(), self.month(), self.day())
+    }
+}
+
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct FatTime(u16);
+
+
+impl FatTime {
+    pub const MIDNIGHT: FatTime = FatTime(0);
+
+
+    /// Odd seconds are rounded down; FAT stores seconds with a 2 second resolution.
+    pub fn new(hour: u8, minute: u8, second: u8) -> FatResult<FatTime> {
+        if 23 < hour || 59 < minute || 59 < second {
+            return Err(FatError::InvalidTime(hour, minute, second));
+        }
+
+        Ok(Self(((hour as u16) << 11) | ((minute as u16) << 5) | (second / 2) as u16))
+    }
+
+
+    #[inline]
+    pub const fn from_raw(raw: u16) -> FatTime {
+        Self(raw)
+    }
+
+
+    #[inline]
+    pub const fn raw(&self) -> u16 {
+        self.0
+    }
+
+
+    #[inline]
+    pub const fn hour(&self) -> u8 {
+        (self.0 >> 11) as u8
+    }
+
+
+    #[inline]
+    pub const fn minute(&self) -> u8 {
+        ((self.0 >> 5) & 0x3F) as u8
+    }
+
+
+    #[inline]
+    pub const fn second(&self) -> u8 {
+        (self.0 & 0x1F) as u8 * 2
+    }
+
+
+    #[inline]
+    pub const fn is_valid(&self) -> bool {
+        self.hour() < 24 && self.minute() < 60 && self.second() < 60
+    }
+
+
+    pub fn seconds_since_midnight(&self) -> FatResult<u64> {
+        if !self.is_valid() {
+            return Err(FatError::InvalidTime(self.hour(), self.minute(), self.second()));
+        }
+
+        Ok(self.hour() as u64 * 3600 + self.minute() as u64 * 60 + self.second() as u64)
+    }
+}
+
+
+impl Debug for FatTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour(), self.minute(), self.second())
+    }
+}
+
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct FatDateTime {
+    date: FatDate,
+
+    time: FatTime,
+
+    centiseconds: u8,
+}
+
+
+impl FatDateTime {
+    pub const EPOCH: FatDateTime = FatDateTime::new(FatDate::EPOCH, FatTime::MIDNIGHT);
+
+
+    #[inline]
+    pub const fn new(date: FatDate, time: FatTime) -> FatDateTime {
+        Self {
+            date,
+            time,
+            centiseconds: 0,
+        }
+    }
+
+
+    /// Builds a timestamp from raw on-disk fields without validating them; see [`FatDateTime::is_valid`].
+    #[inline]
+    pub const fn from_raw(date: FatDate, time: FatTime, centiseconds: u8) -> FatDateTime {
+        Self {
+            date,
+            time,
+            centiseconds,
+        }
+    }
+
+
+    /// `centiseconds` is the 10ms count (0..=199) stored next to the creation time.
+    pub fn with_centiseconds(self, centiseconds: u8) -> FatResult<FatDateTime> {
+        if 199 < centiseconds {
+            return Err(FatError::InvalidCentiseconds(centiseconds));
+        }
+
+        Ok(Self {
+            centiseconds,
+            ..self
+        })
+    }
+
+
+    pub fn from_unix_seconds(seconds: u64) -> FatResult<FatDateTime> {
+        let date = FatDate::from_days_since_unix_epoch(seconds / SECONDS_PER_DAY)
+            .map_err(|_| FatError::TimestampOutOfRange(seconds))?;
+
+        let seconds_of_day = seconds % SECONDS_PER_DAY;
+        let time = FatTime(
+            (((seconds_of_day / 3600) as u16) << 11)
+                | ((((seconds_of_day / 60) % 60) as u16) << 5)
+                | ((seconds_of_day % 60) / 2) as u16
+        );
+
+        Ok(Self {
+            date,
+            time,
+            centiseconds: (seconds_of_day & 1) as u8 * 100,
+        })
+    }
+
+
+    #[inline]
+    pub const fn date(&self) -> FatDate {
+        self.date
+    }
+
+
+    #[inline]
+    pub const fn time(&self) -> FatTime {
+        self.time
+    }
+
+
+    #[inline]
+    pub const fn centiseconds(&self) -> u8 {
+        self.centiseconds
+    }
+
+
+    #[inline]
+    pub const fn is_valid(&self) -> bool {
+        self.date.is_valid() && self.time.is_valid() && self.centiseconds <= 199
+    }
+
+
+    /// FAT does not record a time zone, so the stored local time is treated as UTC.
+    pub fn to_unix_seconds(&self) -> FatResult<u64> {
+        let days = self.date.days_since_unix_epoch()?;
+        let seconds = self.time.seconds_since_midnight()?;
+
+        Ok(days * SECONDS_PER_DAY + seconds + (self.centiseconds / 100) as u64)
+    }
+}
+
+
+impl Debug for FatDateTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}T{:?}.{:02}", self.date, self.time, self.centiseconds)
+    }
+}
+
+
+pub trait TimeProvider {
+    fn now(&self) -> FatDateTime;
+}
+
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct EpochTimeProvider;
+
+
+impl TimeProvider for EpochTimeProvider {
+    #[inline]
+    fn now(&self) -> FatDateTime {
+        FatDateTime::EPOCH
+    }
+}
+
+
+/// Reads the system clock.
+///
+/// FAT timestamps are conventionally local time, but this provider stamps UTC
+/// because `std` has no time zone support; supply your own [`TimeProvider`] for local time.
+#[cfg(feature = "std")]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SystemTimeProvider;
+
+
+#[cfg(feature = "std")]
+impl TimeProvider for SystemTimeProvider {
+    fn now(&self) -> FatDateTime {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()
+            .and_then(|now| FatDateTime::from_unix_seconds(now.as_secs()).ok())
+            .unwrap_or(FatDateTime::EPOCH)
+    }
+}
+
+
+#[inline]
+const fn is_leap_year(year: u16) -> bool {
+    (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400)
+}
+
+
+const fn is_valid_date(year: u16, month: u8, day: u8) -> bool {
+    FAT_EPOCH_YEAR <= year && year <= MAX_YEAR && 1 <= month && month <= 12 && 1 <= day && day <= days_in_month(year, month)
+}
+
+
+const fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::error::FatError;
+    use crate::time::{EpochTimeProvider, FatDate, FatDateTime, FatTime, TimeProvider};
+    #[cfg(feature = "std")]
+    use crate::time::SystemTimeProvider;
+
+    #[test]
+    fn it_decode_date_and_time() {
+        let date = FatDate::from_raw(0x56EC);
+        let time = FatTime::from_raw(0x52E2);
+
+        assert_eq!((date.year(), date.month(), date.day()), (2023, 7, 12));
+        assert_eq!((time.hour(), time.minute(), time.second()), (10, 23, 4));
+        assert_eq!(FatDate::new(2023, 7, 12), Ok(date));
+        assert_eq!(FatTime::new(10, 23, 5), Ok(time));
+    }
+
+
+    #[test]
+    fn it_failed_new_invalid_date_and_time() {
+        assert_eq!(FatDate::new(1979, 12, 31), Err(FatError::InvalidDate(1979, 12, 31)));
+        assert_eq!(FatDate::new(2023, 2, 29), Err(FatError::InvalidDate(2023, 2, 29)));
+        assert!(FatDate::new(2024, 2, 29).is_ok());
+        assert_eq!(FatDate::new(2108, 1, 1), Err(FatError::InvalidDate(2108, 1, 1)));
+        assert_eq!(FatTime::new(24, 0, 0), Err(FatError::InvalidTime(24, 0, 0)));
+        assert!(!FatDate::from_raw(0).is_valid());
+        assert_eq!(FatDateTime::EPOCH.with_centiseconds(200), Err(FatError::InvalidCentiseconds(200)));
+        assert!(!FatDateTime::from_raw(FatDate::EPOCH, FatTime::MIDNIGHT, 200).is_valid());
+    }
+
+
+    #[test]
+    fn it_convert_to_unix_seconds() {
+        let date_time = FatDateTime::new(FatDate::from_raw(0x56EC), FatTime::from_raw(0x52E2))
+            .with_centiseconds(151)
+            .unwrap();
+
+        assert_eq!(FatDateTime::EPOCH.to_unix_seconds(), Ok(315_532_800));
+        assert_eq!(date_time.to_unix_seconds(), Ok(1_689_157_385));
+        assert_eq!(
+            FatDateTime::new(FatDate::from_raw(0), FatTime::MIDNIGHT).to_unix_seconds(),
+            Err(FatError::InvalidDate(1980, 0, 0))
+        );
+    }
+
+
+    #[test]
+    fn it_epoch_time_provider() {
+        assert_eq!(EpochTimeProvider.now(), FatDateTime::EPOCH);
+        assert_eq!(FatDateTime::EPOCH.date(), FatDate::new(1980, 1, 1).unwrap());
+    }
+
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn it_system_time_provider() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        assert!(now.abs_diff(SystemTimeProvider.now().to_unix_seconds().unwrap()) <= 2);
+    }
+
+
+    #[test]
+    fn it_convert_from_unix_seconds() {
+        let date_time = FatDateTime::from_unix_seconds(1_689_157_385).unwrap();
+
+        assert_eq!(date_time.date(), FatDate::new(2023, 7, 12).unwrap());
+        assert_eq!(date_time.time(), FatTime::new(10, 23, 4).unwrap());
+        assert_eq!(date_time.centiseconds(), 100);
+        assert_eq!(date_time.to_unix_seconds(), Ok(1_689_157_385));
+        assert_eq!(FatDateTime::from_unix_seconds(315_532_799), Err(FatError::TimestampOutOfRange(315_532_799)));
+        assert_eq!(FatDateTime::from_unix_seconds(4_354_819_200), Err(FatError::TimestampOutOfRange(4_354_819_200)));
+    }
+}